@@ -15,8 +15,11 @@ use serde::Serialize;
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
+use std::future::Future;
 use tracing::trace;
 
+pub mod retry;
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug, Snafu)]
@@ -223,6 +226,52 @@ impl Client {
         self.list(namespace, &list_params).await
     }
 
+    /// Lists resources matching the LabelSelector across all namespaces.
+    ///
+    /// This issues a cluster-wide list request, which requires the service account to be granted
+    /// `list` on the resource via a `ClusterRole` (a namespaced `Role` is not sufficient). The
+    /// results are requested in pages of [`LIST_PAGE_SIZE`] objects and collected into a single
+    /// [`Vec`].
+    ///
+    /// # Arguments
+    ///
+    /// - `selector` - A reference to a `LabelSelector` to filter the listed resources. An empty
+    ///   selector matches all resources.
+    pub async fn list_all_namespaces<T>(&self, selector: &LabelSelector) -> Result<Vec<T>>
+    where
+        T: Clone + Debug + DeserializeOwned + Resource,
+        <T as Resource>::DynamicType: Default,
+    {
+        let selector_string = selector
+            .to_query_string()
+            .context(SelectorToQueryStringSnafu)?;
+        trace!(
+            "Listing in all namespaces for LabelSelector [{}]",
+            selector_string
+        );
+
+        let api = self.get_all_api::<T>();
+        let list_params = ListParams {
+            label_selector: (!selector_string.is_empty()).then_some(selector_string),
+            limit: Some(LIST_PAGE_SIZE),
+            ..ListParams::default()
+        };
+
+        collect_pages(|continue_token| {
+            let api = api.clone();
+            let list_params = ListParams {
+                continue_token,
+                ..list_params.clone()
+            };
+
+            async move {
+                let list = api.list(&list_params).await.context(ListResourcesSnafu)?;
+                Ok((list.items, list.metadata.continue_))
+            }
+        })
+        .await
+    }
+
     /// Creates a new resource.
     pub async fn create<T>(&self, resource: &T) -> Result<T>
     where
@@ -622,6 +671,33 @@ where
     }
 }
 
+/// The amount of objects requested per page by [`Client::list_all_namespaces`].
+pub const LIST_PAGE_SIZE: u32 = 500;
+
+/// Collects the items of all pages returned by `fetch_page`.
+///
+/// `fetch_page` is called with the continue token of the previous page (`None` for the first
+/// page) and returns the items of the page as well as the continue token for the next one. An
+/// absent or empty continue token marks the last page.
+async fn collect_pages<T, F, Fut>(mut fetch_page: F) -> Result<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>>,
+{
+    let mut items = Vec::new();
+    let mut continue_token = None;
+
+    loop {
+        let (page, next_token) = fetch_page(continue_token).await?;
+        items.extend(page);
+
+        match next_token {
+            Some(token) if !token.is_empty() => continue_token = Some(token),
+            _ => return Ok(items),
+        }
+    }
+}
+
 pub async fn create_client(field_manager: Option<String>) -> Result<Client> {
     let kubeconfig: Config = kube::Config::infer()
         .await
@@ -635,12 +711,12 @@ pub async fn create_client(field_manager: Option<String>) -> Result<Client> {
 #[cfg(test)]
 mod tests {
     use futures::StreamExt;
-    use k8s_openapi::api::core::v1::{Container, Pod, PodSpec};
+    use k8s_openapi::api::core::v1::{ConfigMap, Container, Pod, PodSpec};
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
     use kube::api::{ObjectMeta, PostParams, ResourceExt};
     use kube::runtime::watcher;
     use kube::runtime::watcher::Event;
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, BTreeSet};
     use std::time::Duration;
     use tokio::time::error::Elapsed;
 
@@ -797,4 +873,90 @@ mod tests {
             .await
             .expect("Expected Pod to be deleted");
     }
+
+    #[tokio::test]
+    async fn collect_pages_from_multiple_namespaces() {
+        let pages = vec![
+            (
+                vec![config_map("a", "default"), config_map("b", "kube-system")],
+                Some("page-2".to_owned()),
+            ),
+            (
+                vec![config_map("c", "stackable")],
+                Some("page-3".to_owned()),
+            ),
+            (vec![config_map("d", "default")], None),
+        ];
+        let mut requested_tokens = Vec::new();
+
+        let config_maps = super::collect_pages(|continue_token| {
+            requested_tokens.push(continue_token);
+            let page = pages[requested_tokens.len() - 1].clone();
+            async move { Ok(page) }
+        })
+        .await
+        .expect("collecting pages must succeed");
+
+        assert_eq!(
+            requested_tokens,
+            vec![None, Some("page-2".to_owned()), Some("page-3".to_owned())]
+        );
+        assert_eq!(
+            config_maps
+                .iter()
+                .map(|cm| (cm.name_any(), cm.namespace().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a".to_owned(), "default".to_owned()),
+                ("b".to_owned(), "kube-system".to_owned()),
+                ("c".to_owned(), "stackable".to_owned()),
+                ("d".to_owned(), "default".to_owned()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_pages_stops_at_empty_continue_token() {
+        let mut calls = 0;
+
+        let config_maps = super::collect_pages(|_| {
+            calls += 1;
+            async { Ok((vec![config_map("a", "default")], Some(String::new()))) }
+        })
+        .await
+        .expect("collecting pages must succeed");
+
+        assert_eq!(calls, 1);
+        assert_eq!(config_maps.len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "Tests depending on Kubernetes are not ran by default"]
+    async fn k8s_test_list_all_namespaces() {
+        let client = super::create_client(None)
+            .await
+            .expect("KUBECONFIG variable must be configured.");
+
+        let config_maps: Vec<ConfigMap> = client
+            .list_all_namespaces(&LabelSelector::default())
+            .await
+            .expect("Expected to list ConfigMaps in all namespaces");
+
+        // Every cluster contains at least the kube-root-ca.crt ConfigMap in the default and
+        // kube-system namespaces.
+        let namespaces: BTreeSet<_> = config_maps.iter().filter_map(|cm| cm.namespace()).collect();
+        assert!(namespaces.contains("default"));
+        assert!(namespaces.contains("kube-system"));
+    }
+
+    fn config_map(name: &str, namespace: &str) -> ConfigMap {
+        ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some(namespace.to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        }
+    }
 }
@@ -0,0 +1,250 @@
+//! Retrying of failed API calls using an exponential backoff.
+//!
+//! The delay between two attempts doubles with every retry (up to a maximum delay). To avoid
+//! that many operators retry at the very same time after the API server recovered (the
+//! "thundering herd"), a [`Jitter`] strategy randomizes the delays.
+
+use std::{
+    future::Future,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tracing::debug;
+
+use crate::client::Result;
+
+/// The strategy used to randomize the delays of a [`RetryPolicy`].
+///
+/// See <https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/> for a
+/// comparison of the strategies.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Jitter {
+    /// Uses the exponential backoff delays as is.
+    None,
+
+    /// Picks a random delay between zero and the exponential backoff delay.
+    #[default]
+    Full,
+
+    /// Picks a random delay between half of the exponential backoff delay and the full delay.
+    Equal,
+}
+
+impl Jitter {
+    fn apply(self, delay: Duration, rng: &mut SplitMix64) -> Duration {
+        let millis = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+
+        match self {
+            Jitter::None => delay,
+            Jitter::Full => Duration::from_millis(rng.next_below(millis.saturating_add(1))),
+            Jitter::Equal => {
+                let half = millis / 2;
+                Duration::from_millis(half + rng.next_below(millis - half + 1))
+            }
+        }
+    }
+}
+
+/// Describes how often and with which delays failed API calls are retried, see [`retry`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+
+    /// The delay before the first retry, which doubles with every further retry.
+    pub initial_delay: Duration,
+
+    /// The upper bound of the (not yet randomized) delay between two attempts.
+    pub max_delay: Duration,
+
+    /// The strategy used to randomize the delays.
+    pub jitter: Jitter,
+
+    /// The seed used to randomize the delays. If not set, a seed is derived from the current
+    /// time. Setting a seed makes the delays deterministic, which is mostly useful in tests.
+    pub seed: Option<u64>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: Jitter::default(),
+            seed: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delays before each retry, which contains [`RetryPolicy::max_retries`] items.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> {
+        let policy = *self;
+        let mut rng = SplitMix64(self.seed.unwrap_or_else(time_based_seed));
+
+        (0..self.max_retries).map(move |retry| policy.jitter.apply(policy.backoff(retry), &mut rng))
+    }
+
+    /// Returns the exponential backoff delay (without jitter) before the `retry`th retry.
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// Calls `operation` until it succeeds or the retries of the `policy` are exhausted, in which case
+/// the last error is returned.
+///
+/// ```no_run
+/// # use k8s_openapi::api::core::v1::ConfigMap;
+/// # use stackable_operator::client::{retry::{retry, RetryPolicy}, Client};
+/// # async fn test(client: &Client) -> stackable_operator::client::Result<()> {
+/// let config_map = retry(&RetryPolicy::default(), || {
+///     client.get::<ConfigMap>("my-config-map", "default")
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut delays = policy.delays();
+
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) => match delays.next() {
+                Some(delay) => {
+                    debug!(error = %err, ?delay, "API call failed, retrying");
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(err),
+            },
+        }
+    }
+}
+
+fn time_based_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// A minimal (non-cryptographic) pseudo random number generator, which is good enough to
+/// randomize delays and fully deterministic for a given seed.
+///
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`. `bound` must not be zero.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::client::Error;
+
+    fn policy(jitter: Jitter) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter,
+            seed: Some(42),
+        }
+    }
+
+    #[test]
+    fn split_mix_64() {
+        assert_eq!(SplitMix64(0).next_u64(), 0xe220a8397b1dcdaf);
+    }
+
+    #[rstest]
+    #[case::none(Jitter::None, [100, 200, 400, 800, 1000])]
+    #[case::full(Jitter::Full, [23, 49, 111, 657, 475])]
+    #[case::equal(Jitter::Equal, [63, 163, 233, 504, 723])]
+    fn delays(#[case] jitter: Jitter, #[case] expected: [u64; 5]) {
+        let policy = policy(jitter);
+
+        let delays = policy.delays().collect::<Vec<_>>();
+        assert_eq!(delays, expected.map(Duration::from_millis));
+
+        // The delays must be deterministic for a fixed seed
+        assert_eq!(delays, policy.delays().collect::<Vec<_>>());
+
+        for (retry, delay) in (0..).zip(delays) {
+            let backoff = policy.backoff(retry);
+            assert!(delay <= backoff);
+            if jitter == Jitter::Equal {
+                assert!(delay >= backoff / 2);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_until_success() {
+        let counter = AtomicU32::new(0);
+        let attempts = &counter;
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_millis(1),
+            ..policy(Jitter::None)
+        };
+
+        let result = retry(&policy, move || async move {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0..=1 => Err(Error::MissingObjectKey {
+                    key: "metadata.name",
+                }),
+                attempt => Ok(attempt),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_exhausted() {
+        let counter = AtomicU32::new(0);
+        let attempts = &counter;
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(1),
+            ..policy(Jitter::Full)
+        };
+
+        let result = retry(&policy, move || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(Error::MissingObjectKey {
+                key: "metadata.name",
+            })
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::MissingObjectKey { .. })));
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,135 @@
+//! Resolution of the S3 host to IP addresses, which is useful for operators
+//! pre-resolving the endpoint, e.g. to set `hostAliases` or to detect changes.
+//!
+//! This module is only available with the `dns` feature enabled.
+
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use snafu::{ResultExt, Snafu};
+
+use crate::commons::s3::InlinedS3BucketSpec;
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to resolve S3 host {host:?}"))]
+    ResolveHost {
+        source: std::io::Error,
+        host: String,
+    },
+
+    #[snafu(display("timed out after {timeout:?} while resolving S3 host {host:?}"))]
+    ResolveHostTimeout { host: String, timeout: Duration },
+}
+
+impl InlinedS3BucketSpec {
+    /// Resolves the host of the connection to its IP addresses. The lookup is
+    /// cancelled after the provided `timeout`.
+    ///
+    /// Hosts which already are IP literals are returned as is, without doing
+    /// any DNS lookup. If no connection or host is configured, no addresses are
+    /// returned. The returned addresses are sorted and free of duplicates.
+    pub async fn resolve_host_ips(&self, timeout: Duration) -> Result<Vec<IpAddr>> {
+        let Some(host) = self
+            .connection
+            .as_ref()
+            .and_then(|connection| connection.host.as_deref())
+        else {
+            return Ok(Vec::new());
+        };
+
+        // IPv6 literals might be enclosed in brackets.
+        if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse() {
+            return Ok(vec![ip]);
+        }
+
+        // The port is irrelevant for the lookup, but required by the API.
+        let addresses = tokio::time::timeout(timeout, tokio::net::lookup_host((host, 0)))
+            .await
+            .map_err(|_| Error::ResolveHostTimeout {
+                host: host.to_owned(),
+                timeout,
+            })?
+            .context(ResolveHostSnafu { host })?;
+
+        Ok(addresses
+            .map(|address: SocketAddr| address.ip())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::commons::s3::S3ConnectionSpec;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn bucket_with_host(host: Option<&str>) -> InlinedS3BucketSpec {
+        InlinedS3BucketSpec::builder()
+            .bucket_name("my-bucket")
+            .connection(S3ConnectionSpec {
+                host: host.map(ToOwned::to_owned),
+                ..Default::default()
+            })
+            .build()
+    }
+
+    #[rstest]
+    #[case("192.168.0.1", "192.168.0.1")]
+    #[case("fe80::1", "fe80::1")]
+    #[case("[fe80::1]", "fe80::1")]
+    #[tokio::test]
+    async fn test_resolve_ip_literal(#[case] host: &str, #[case] expected: IpAddr) {
+        let ips = bucket_with_host(Some(host))
+            .resolve_host_ips(TIMEOUT)
+            .await
+            .expect("IP literals must not be resolved");
+
+        assert_eq!(ips, vec![expected]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_localhost() {
+        let ips = bucket_with_host(Some("localhost"))
+            .resolve_host_ips(TIMEOUT)
+            .await
+            .expect("localhost must be resolvable");
+
+        assert!(ips.iter().all(IpAddr::is_loopback));
+        assert!(!ips.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_unknown_host() {
+        // The .invalid TLD is guaranteed to never resolve, see RFC 2606.
+        let err = bucket_with_host(Some("does-not-exist.invalid"))
+            .resolve_host_ips(TIMEOUT)
+            .await
+            .expect_err("unknown hosts must not be resolvable");
+
+        assert!(matches!(
+            err,
+            Error::ResolveHost { .. } | Error::ResolveHostTimeout { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_without_host() {
+        let ips = bucket_with_host(None)
+            .resolve_host_ips(TIMEOUT)
+            .await
+            .expect("missing hosts resolve to nothing");
+
+        assert!(ips.is_empty());
+    }
+}
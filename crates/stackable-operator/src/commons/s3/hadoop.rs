@@ -0,0 +1,390 @@
+//! Maps S3 connections to the `fs.s3a.*` properties of the Hadoop S3A connector.
+//!
+//! Many products (like Druid, Hive, Spark or Trino) access S3 using the
+//! Hadoop S3A connector. The connector is configured via properties in the
+//! `core-site.xml` (or the product specific equivalent).
+
+use std::collections::BTreeMap;
+
+use crate::commons::{
+    authentication::tls::{Tls, TlsVerification},
+    s3::{InlinedS3BucketSpec, RequestPayer, S3AccessStyle, S3ConnectionSpec},
+};
+
+pub const ENDPOINT: &str = "fs.s3a.endpoint";
+pub const PATH_STYLE_ACCESS: &str = "fs.s3a.path.style.access";
+pub const SSL_ENABLED: &str = "fs.s3a.connection.ssl.enabled";
+pub const MULTIPART_THRESHOLD: &str = "fs.s3a.multipart.threshold";
+pub const MULTIPART_SIZE: &str = "fs.s3a.multipart.size";
+pub const REQUESTER_PAYS_ENABLED: &str = "fs.s3a.requester.pays.enabled";
+pub const ATTEMPTS_MAXIMUM: &str = "fs.s3a.attempts.maximum";
+
+/// The prefix Spark requires for Hadoop properties, which are passed to Spark as `spark.hadoop.*`.
+pub const SPARK_HADOOP_PREFIX: &str = "spark.hadoop.";
+
+/// The JVM system property of the AWS SDK (used by the S3A connector) to skip the verification
+/// of the certificates presented by the S3 server.
+pub const AWS_SDK_DISABLE_CERT_CHECKING: &str = "com.amazonaws.sdk.disableCertChecking";
+
+impl S3ConnectionSpec {
+    /// Returns the Hadoop S3A properties of this connection.
+    ///
+    /// Only properties, which are derived from fields set in the connection,
+    /// are returned. Credentials are not part of the returned properties, as
+    /// they should be provided via environment variables or files mounted
+    /// from the credentials [`SecretClass`](crate::commons::secret_class::SecretClassVolume).
+    /// Sizes are rendered in bytes. `fs.s3a.path.style.access` is always set, using the
+    /// [`S3ConnectionSpec::access_style_or_default`].
+    pub fn hadoop_config(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if let Some(endpoint) = self.endpoint() {
+            config.insert(ENDPOINT.to_string(), endpoint);
+        }
+
+        config.insert(
+            PATH_STYLE_ACCESS.to_string(),
+            (self.access_style_or_default() == S3AccessStyle::Path).to_string(),
+        );
+
+        config.insert(SSL_ENABLED.to_string(), self.tls.is_some().to_string());
+
+        if let Some(threshold) = &self.multipart_threshold {
+            config.insert(
+                MULTIPART_THRESHOLD.to_string(),
+                threshold.as_bytes().to_string(),
+            );
+        }
+
+        if let Some(part_size) = &self.multipart_part_size {
+            config.insert(MULTIPART_SIZE.to_string(), part_size.as_bytes().to_string());
+        }
+
+        if let Some(request_payer) = &self.request_payer {
+            config.insert(
+                REQUESTER_PAYS_ENABLED.to_string(),
+                (request_payer == &RequestPayer::Requester).to_string(),
+            );
+        }
+
+        if let Some(max_retries) = self.max_retries {
+            config.insert(ATTEMPTS_MAXIMUM.to_string(), max_retries.to_string());
+        }
+
+        config
+    }
+
+    /// Returns the [Hadoop properties](Self::hadoop_config) of this connection prefixed with
+    /// `spark.hadoop.`, as expected by Spark.
+    pub fn to_spark_config(&self) -> BTreeMap<String, String> {
+        self.hadoop_config()
+            .into_iter()
+            .map(|(key, value)| (format!("{SPARK_HADOOP_PREFIX}{key}"), value))
+            .collect()
+    }
+
+    /// Returns the JVM system properties required by the AWS SDK, which is used by the Hadoop
+    /// S3A connector. Contrary to the [Hadoop properties](Self::hadoop_config), these must be
+    /// passed to the JVM, e.g. via `-D<key>=<value>`.
+    ///
+    /// Certificate verification is only skipped if TLS is enabled using
+    /// [`TlsVerification::None`], which means TLS encryption without verification. Without TLS,
+    /// nothing is encrypted and thus nothing can be verified in the first place.
+    pub fn jvm_system_properties(&self) -> BTreeMap<String, String> {
+        let mut properties = BTreeMap::new();
+
+        if let Some(Tls {
+            verification: TlsVerification::None {},
+        }) = &self.tls
+        {
+            properties.insert(
+                AWS_SDK_DISABLE_CERT_CHECKING.to_string(),
+                "true".to_string(),
+            );
+        }
+
+        properties
+    }
+}
+
+impl InlinedS3BucketSpec {
+    /// Returns the Hadoop S3A properties to access this bucket, see
+    /// [`S3ConnectionSpec::hadoop_config`].
+    ///
+    /// Contrary to the properties of the connection, the endpoint always includes the port (see
+    /// [`InlinedS3BucketSpec::endpoint`]). An empty map is returned, if the bucket has no
+    /// connection.
+    pub fn hadoop_config(&self) -> BTreeMap<String, String> {
+        let Some(connection) = &self.connection else {
+            return BTreeMap::new();
+        };
+
+        let mut config = connection.hadoop_config();
+
+        if let Some(endpoint) = self.endpoint() {
+            config.insert(ENDPOINT.to_string(), endpoint);
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::commons::s3::ConnectionType;
+
+    #[test]
+    fn test_hadoop_config_multipart() {
+        let connection: S3ConnectionSpec = serde_yaml::from_str(
+            "
+            host: minio
+            port: 9000
+            accessStyle: Path
+            multipartThreshold: 64Mi
+            multipartPartSize: 32Mi
+            ",
+        )
+        .expect("valid S3 connection");
+
+        let config = connection.hadoop_config();
+        assert_eq!(
+            config,
+            BTreeMap::from([
+                (ENDPOINT.to_string(), "http://minio:9000".to_string()),
+                (PATH_STYLE_ACCESS.to_string(), "true".to_string()),
+                (SSL_ENABLED.to_string(), "false".to_string()),
+                (MULTIPART_THRESHOLD.to_string(), "67108864".to_string()),
+                (MULTIPART_SIZE.to_string(), "33554432".to_string()),
+            ])
+        );
+    }
+
+    #[rstest]
+    #[case(Some(RequestPayer::Requester), Some("true"))]
+    #[case(Some(RequestPayer::BucketOwner), Some("false"))]
+    #[case(None, None)]
+    fn test_hadoop_config_request_payer(
+        #[case] request_payer: Option<RequestPayer>,
+        #[case] expected: Option<&str>,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            request_payer,
+            ..Default::default()
+        };
+
+        let config = connection.hadoop_config();
+        assert_eq!(
+            config.get(REQUESTER_PAYS_ENABLED).map(String::as_str),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(Some(5), Some("5"))]
+    #[case(Some(0), Some("0"))]
+    #[case(None, None)]
+    fn test_hadoop_config_max_retries(
+        #[case] max_retries: Option<u32>,
+        #[case] expected: Option<&str>,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            max_retries,
+            ..Default::default()
+        };
+
+        let config = connection.hadoop_config();
+        assert_eq!(config.get(ATTEMPTS_MAXIMUM).map(String::as_str), expected);
+    }
+
+    #[rstest]
+    #[case::no_tls(None, "false", None)]
+    #[case::verify(Some("verification: {server: {caCert: {webPki: {}}}}"), "true", None)]
+    #[case::skip_verify(Some("verification: {none: {}}"), "true", Some("true"))]
+    fn test_tls_modes(
+        #[case] tls: Option<&str>,
+        #[case] ssl_enabled: &str,
+        #[case] disable_cert_checking: Option<&str>,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            port: Some(9000),
+            tls: tls.map(|tls| serde_yaml::from_str(tls).expect("valid TLS settings")),
+            ..Default::default()
+        };
+
+        let config = connection.hadoop_config();
+        assert_eq!(
+            config.get(SSL_ENABLED).map(String::as_str),
+            Some(ssl_enabled)
+        );
+        assert!(!config.contains_key(AWS_SDK_DISABLE_CERT_CHECKING));
+
+        let properties = connection.jvm_system_properties();
+        assert_eq!(
+            properties
+                .get(AWS_SDK_DISABLE_CERT_CHECKING)
+                .map(String::as_str),
+            disable_cert_checking
+        );
+    }
+
+    #[test]
+    fn test_spark_config() {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            access_style: Some(S3AccessStyle::Path),
+            max_retries: Some(3),
+            ..Default::default()
+        };
+
+        let hadoop_config = connection.hadoop_config();
+        let spark_config = connection.to_spark_config();
+        assert_eq!(spark_config.len(), hadoop_config.len());
+
+        for (key, value) in &hadoop_config {
+            assert!(key.starts_with("fs.s3a."));
+            assert_eq!(
+                spark_config.get(&format!("spark.hadoop.{key}")),
+                Some(value)
+            );
+        }
+        assert_eq!(
+            spark_config
+                .get("spark.hadoop.fs.s3a.endpoint")
+                .map(String::as_str),
+            Some("http://minio")
+        );
+    }
+
+    #[rstest]
+    #[case::aws(Some(ConnectionType::Aws), "minio", Some("false"))]
+    #[case::s3_compatible(Some(ConnectionType::S3Compatible), "minio", Some("true"))]
+    #[case::aws_detected(None, "s3.eu-central-1.amazonaws.com", Some("false"))]
+    #[case::explicit_type_wins(
+        Some(ConnectionType::S3Compatible),
+        "s3.eu-central-1.amazonaws.com",
+        Some("true")
+    )]
+    #[case::unknown(None, "minio", Some("true"))]
+    fn test_hadoop_config_connection_type(
+        #[case] connection_type: Option<ConnectionType>,
+        #[case] host: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some(host.to_owned()),
+            connection_type,
+            ..Default::default()
+        };
+
+        let config = connection.hadoop_config();
+        assert_eq!(config.get(PATH_STYLE_ACCESS).map(String::as_str), expected);
+
+        // The bucket must use the same access style as its connection
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(connection)
+            .build();
+        assert_eq!(
+            bucket.hadoop_config().get(PATH_STYLE_ACCESS),
+            config.get(PATH_STYLE_ACCESS)
+        );
+    }
+
+    #[test]
+    fn test_hadoop_config_connection_type_differs() {
+        let connection = |connection_type| S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            connection_type: Some(connection_type),
+            ..Default::default()
+        };
+
+        let aws = connection(ConnectionType::Aws).hadoop_config();
+        let s3_compatible = connection(ConnectionType::S3Compatible).hadoop_config();
+        assert_ne!(aws, s3_compatible);
+
+        // An explicit access style always wins over the default of the connection type
+        let connection = S3ConnectionSpec {
+            access_style: Some(S3AccessStyle::VirtualHosted),
+            ..connection(ConnectionType::S3Compatible)
+        };
+        assert_eq!(connection.hadoop_config(), aws);
+    }
+
+    #[test]
+    fn test_bucket_hadoop_config_minio() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("my-bucket")
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                port: Some(9000),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(
+            bucket.hadoop_config(),
+            BTreeMap::from([
+                (ENDPOINT.to_string(), "http://minio:9000".to_string()),
+                (PATH_STYLE_ACCESS.to_string(), "true".to_string()),
+                (SSL_ENABLED.to_string(), "false".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bucket_hadoop_config() {
+        let no_connection = InlinedS3BucketSpec::builder()
+            .bucket_name("my-bucket")
+            .build();
+        assert!(no_connection.hadoop_config().is_empty());
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("s3.eu-central-1.amazonaws.com".to_owned()),
+                tls: Some(
+                    serde_yaml::from_str("verification: {server: {caCert: {webPki: {}}}}")
+                        .expect("valid TLS settings"),
+                ),
+                ..Default::default()
+            })
+            .build();
+
+        let config = bucket.hadoop_config();
+        assert_eq!(
+            config.get(ENDPOINT).map(String::as_str),
+            Some("https://s3.eu-central-1.amazonaws.com:443")
+        );
+        assert_eq!(
+            config.get(PATH_STYLE_ACCESS).map(String::as_str),
+            Some("false")
+        );
+        assert_eq!(config.get(SSL_ENABLED).map(String::as_str), Some("true"));
+    }
+
+    #[test]
+    fn test_hadoop_config_minimal() {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            ..Default::default()
+        };
+
+        let config = connection.hadoop_config();
+        assert_eq!(
+            config.get(ENDPOINT).map(String::as_str),
+            Some("http://minio")
+        );
+        assert_eq!(
+            config.get(PATH_STYLE_ACCESS).map(String::as_str),
+            Some("true")
+        );
+        assert!(!config.contains_key(MULTIPART_THRESHOLD));
+        assert!(!config.contains_key(MULTIPART_SIZE));
+        assert!(!config.contains_key(REQUESTER_PAYS_ENABLED));
+        assert!(!config.contains_key(ATTEMPTS_MAXIMUM));
+    }
+}
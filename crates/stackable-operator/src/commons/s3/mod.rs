@@ -0,0 +1,3693 @@
+//! Implementation of the bucket definition as described in
+//! the <https://github.com/stackabletech/documentation/pull/177>
+//!
+//! Operator CRDs are expected to use the [S3BucketDef] as an entry point to this module
+//! and obtain an [InlinedS3BucketSpec] by calling [`S3BucketDef::resolve`].
+//!
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    net::Ipv6Addr,
+};
+
+use k8s_openapi::{
+    api::{
+        core::v1::{ConfigMap, EnvVar, Volume, VolumeMount},
+        rbac::v1::PolicyRule,
+    },
+    apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule,
+    apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta},
+};
+use kube::{
+    api::{DynamicObject, GroupVersionKind},
+    CustomResource, Resource, ResourceExt,
+};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use url::Url;
+
+use crate::{
+    builder::pod::{container::ContainerBuilder, volume::VolumeMountBuilder, PodBuilder},
+    client::Client,
+    commons::{
+        authentication::{
+            tls::{CaCert, Tls, TlsServerVerification, TlsVerification},
+            SECRET_BASE_PATH,
+        },
+        secret_class::{SecretClassVolume, SecretClassVolumeError},
+    },
+    config::merge::Merge,
+    kvp::{Label, LabelError},
+    memory::MemoryQuantity,
+    utils::crds::add_validation_rules,
+};
+
+pub mod hadoop;
+
+mod namespace;
+pub use namespace::{FixedNamespace, NamespaceResolver, SameNamespace};
+
+mod resolver;
+pub use resolver::S3ConnectionResolver;
+
+#[cfg(feature = "dns")]
+pub mod dns;
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+// The `#[kube]` attributes below require literals, which are kept in sync with these constants.
+/// The API group of the [`S3Bucket`] and [`S3Connection`] CRDs.
+pub const S3_API_GROUP: &str = "s3.stackable.tech";
+
+/// The API version of the [`S3Bucket`] and [`S3Connection`] CRDs.
+pub const S3_API_VERSION: &str = "v1alpha1";
+
+/// The label key tying (child) resources to the [`S3Bucket`] they were created for, see
+/// [`S3Bucket::owned_by_label`].
+pub const OWNED_BY_LABEL_KEY: &str = "s3.stackable.tech/owned-by";
+
+/// The key of the file containing the access key in the credentials
+/// [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass).
+pub const ACCESS_KEY_ID: &str = "accessKey";
+
+/// The key of the file containing the secret key in the credentials
+/// [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass).
+pub const SECRET_ACCESS_KEY: &str = "secretKey";
+
+/// The name of the environment variable containing the path of the access key file, see
+/// [`InlinedS3BucketSpec::credentials_env_vars`].
+pub const ACCESS_KEY_FILE_ENV: &str = "S3_ACCESS_KEY_FILE";
+
+/// The name of the environment variable containing the path of the secret key file, see
+/// [`InlinedS3BucketSpec::credentials_env_vars`].
+pub const SECRET_KEY_FILE_ENV: &str = "S3_SECRET_KEY_FILE";
+
+/// The conventional name of the [`S3Connection`] supplying defaults for other connections, see
+/// [`S3ConnectionSpec::with_cluster_default`].
+pub const DEFAULT_S3_CONNECTION_NAME: &str = "default";
+
+/// The schemes allowed as [S3ConnectionSpec::endpoint_scheme_override].
+pub const ENDPOINT_SCHEMES: [&str; 3] = ["http", "https", "s3a"];
+
+/// The maximum length of a label value, which is shorter than the maximum length of object names.
+const LABEL_VALUE_MAX_LEN: usize = 63;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("missing S3Connection {resource_name:?} in namespace {namespace:?}"))]
+    MissingS3Connection {
+        source: crate::client::Error,
+        resource_name: String,
+        namespace: String,
+    },
+
+    #[snafu(display(
+        "missing ConfigMap {resource_name:?} with the S3 connection in namespace {namespace:?}"
+    ))]
+    MissingS3ConnectionConfigMap {
+        source: crate::client::Error,
+        resource_name: String,
+        namespace: String,
+    },
+
+    #[snafu(display("failed to list S3Connections in namespace {namespace:?}"))]
+    ListS3Connections {
+        source: crate::client::Error,
+        namespace: String,
+    },
+
+    #[snafu(display(
+        "no S3Connection in namespace {namespace:?} matches the label selector {selector:?}"
+    ))]
+    NoMatchingS3Connection {
+        selector: LabelSelector,
+        namespace: String,
+    },
+
+    #[snafu(display("multiple S3Connections ({names:?}) in namespace {namespace:?} match the label selector {selector:?}"))]
+    AmbiguousS3Connection {
+        selector: LabelSelector,
+        namespace: String,
+        names: Vec<String>,
+    },
+
+    #[snafu(display(
+        "failed to get the default S3Connection {resource_name:?} in namespace {namespace:?}"
+    ))]
+    GetDefaultS3Connection {
+        source: crate::client::Error,
+        resource_name: String,
+        namespace: String,
+    },
+
+    #[snafu(display("missing S3Bucket {resource_name:?} in namespace {namespace:?}"))]
+    MissingS3Bucket {
+        source: crate::client::Error,
+        resource_name: String,
+        namespace: String,
+    },
+
+    #[snafu(display(
+        "no namespace given for S3Bucket {resource_name:?} and the client has no default namespace"
+    ))]
+    NoNamespace { resource_name: String },
+
+    #[snafu(display("no namespace given to list the S3Buckets matching the label selector {selector:?} and the client has no default namespace"))]
+    NoNamespaceForSelector { selector: LabelSelector },
+
+    #[snafu(display("failed to list S3Buckets in namespace {namespace:?}"))]
+    ListS3Buckets {
+        source: crate::client::Error,
+        namespace: String,
+    },
+
+    #[snafu(display("failed to inline S3Bucket {resource_name:?}"))]
+    InlineS3Bucket {
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+        resource_name: String,
+    },
+
+    #[snafu(display("failed to convert S3Bucket {resource_name:?} to a DynamicObject"))]
+    S3BucketToDynamicObject {
+        source: serde_json::Error,
+        resource_name: String,
+    },
+
+    #[snafu(display("failed to convert DynamicObject {resource_name:?} to a S3Bucket"))]
+    S3BucketFromDynamicObject {
+        source: serde_json::Error,
+        resource_name: String,
+    },
+
+    #[snafu(display(
+        "DynamicObject {resource_name:?} of type {api_version}/{kind} is not a S3Bucket"
+    ))]
+    UnexpectedDynamicObjectType {
+        resource_name: String,
+        api_version: String,
+        kind: String,
+    },
+
+    #[snafu(display("failed to resolve the namespace of the S3 objects referenced by {owner:?}"))]
+    UnresolvedNamespace { owner: String },
+
+    #[snafu(display("failed to build the volume for the S3 credentials"))]
+    CredentialsVolume { source: SecretClassVolumeError },
+
+    #[snafu(display("the S3 bucket must have a bucket name"))]
+    BucketNameRequired,
+
+    #[snafu(display("the S3 connection must specify a region"))]
+    RegionRequired,
+
+    #[snafu(display("the S3 connection must use TLS"))]
+    TlsRequired,
+
+    #[snafu(display("the S3 bucket has no connection"))]
+    MissingConnection,
+
+    #[snafu(display("the S3 bucket {bucket_name:?} has no connection configured"))]
+    NoS3ConnectionConfigured { bucket_name: String },
+
+    #[snafu(display("the S3 connection has neither a host nor an endpoint override"))]
+    MissingHost,
+
+    #[snafu(display("the S3 connection specifies an invalid {field:?}: {reason}"))]
+    InvalidS3Connection {
+        field: &'static str,
+        reason: &'static str,
+    },
+
+    #[snafu(display("the S3 endpoint {endpoint:?} is not a valid URL"))]
+    InvalidEndpointUrl {
+        source: url::ParseError,
+        endpoint: String,
+    },
+
+    #[snafu(display("the S3 connection uses {protocol} on the {expected} port {port}"))]
+    TlsPortMismatch {
+        protocol: &'static str,
+        expected: &'static str,
+        port: u16,
+    },
+
+    #[snafu(display("missing environment variable {name:?}"))]
+    MissingEnvVar { name: String },
+
+    #[snafu(display("invalid value {value:?} of environment variable {name:?}"))]
+    InvalidEnvVar { name: String, value: String },
+
+    #[snafu(display("failed to parse the S3 connection"))]
+    ParseS3Connection { source: serde_yaml::Error },
+
+    #[snafu(display(
+        "unknown field {field:?} in the S3 connection, expected one of {expected:?}"
+    ))]
+    UnknownS3ConnectionField {
+        field: String,
+        expected: Vec<String>,
+    },
+
+    #[snafu(display("missing key {key:?} in the S3 connection ConfigMap {config_map:?}"))]
+    MissingConfigMapKey { config_map: String, key: String },
+
+    #[snafu(display(
+        "invalid value {value:?} of key {key:?} in the S3 connection ConfigMap {config_map:?}"
+    ))]
+    InvalidConfigMapKey {
+        config_map: String,
+        key: String,
+        value: String,
+    },
+}
+
+/// Returns the [`PolicyRule`]s an operator needs to resolve [`S3Bucket`]s and
+/// [`S3Connection`]s. Operators are expected to embed these rules into the
+/// (Cluster)Role of their service account, so that the RBAC permissions stay in
+/// sync with the API calls done by this module.
+pub fn s3_rbac_policy_rules() -> Vec<PolicyRule> {
+    vec![
+        PolicyRule {
+            api_groups: Some(vec![S3Bucket::group(&()).to_string()]),
+            resources: Some(vec![
+                S3Bucket::plural(&()).to_string(),
+                S3Connection::plural(&()).to_string(),
+            ]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+            ..PolicyRule::default()
+        },
+        // Required to resolve S3ConnectionDef::ConfigMapReference
+        PolicyRule {
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["configmaps".to_string()]),
+            verbs: vec!["get".to_string()],
+            ..PolicyRule::default()
+        },
+    ]
+}
+
+/// S3 bucket specification containing the bucket name and an inlined or referenced connection specification.
+/// Learn more on the [S3 concept documentation](DOCS_BASE_URL_PLACEHOLDER/concepts/s3).
+#[derive(
+    Clone, CustomResource, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize,
+)]
+#[kube(
+    group = "s3.stackable.tech",
+    version = "v1alpha1",
+    kind = "S3Bucket",
+    plural = "s3buckets",
+    crates(
+        kube_core = "kube::core",
+        k8s_openapi = "k8s_openapi",
+        schemars = "schemars"
+    ),
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BucketSpec {
+    /// The name of the S3 bucket.
+    // FIXME: Try to remove the Option<>, as this field should be mandatory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket_name: Option<String>,
+
+    /// The definition of an S3 connection, either inline or as a reference.
+    // FIXME: Try to remove the Option<>, as this field should be mandatory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<S3ConnectionDef>,
+
+    /// An optional name of the connection, which is only used to refer to the connection (e.g. in
+    /// the status or in events) and doesn't affect how the connection is resolved. This is useful
+    /// for inline connections, which don't have a name otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_name: Option<String>,
+
+    /// An optional path (prefix) within the bucket, under which all data is stored, for example
+    /// `tenants/a`. This is useful if multiple users share a single bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+}
+
+impl S3BucketSpec {
+    /// Convenience function to retrieve the spec of a S3 bucket resource from the K8S API service.
+    /// Use [`S3Bucket::get_resource`] to retrieve the metadata as well.
+    pub async fn get(
+        resource_name: &str,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3BucketSpec> {
+        S3Bucket::get_resource(resource_name, client, namespace)
+            .await
+            .map(|crd| crd.spec)
+    }
+
+    /// Like [`S3BucketSpec::get`], but falls back to the
+    /// [default namespace](Client::default_namespace) of the client if no `namespace` is given.
+    ///
+    /// S3 buckets are namespaced, so the cluster scope is never queried. Returns
+    /// [`Error::NoNamespace`] if neither a namespace is given nor the client has a default one.
+    pub async fn get_in_namespace_or_default(
+        resource_name: &str,
+        client: &Client,
+        namespace: Option<&str>,
+    ) -> Result<S3BucketSpec> {
+        let namespace =
+            namespace_or_default(client, namespace).context(NoNamespaceSnafu { resource_name })?;
+
+        Self::get(resource_name, client, namespace).await
+    }
+
+    /// Lists the S3 bucket resources matching the label `selector` and
+    /// [inlines](S3BucketSpec::inlined) each of them. Returns the name of every bucket alongside
+    /// its inlined spec, which is empty if no bucket matches.
+    ///
+    /// Like [`S3BucketSpec::get_in_namespace_or_default`], this falls back to the
+    /// [default namespace](Client::default_namespace) of the client if no `namespace` is given.
+    /// Fails with [`Error::InlineS3Bucket`] naming the bucket if inlining any of them fails.
+    pub async fn list_by_labels(
+        selector: &LabelSelector,
+        client: &Client,
+        namespace: Option<&str>,
+    ) -> Result<Vec<(String, InlinedS3BucketSpec)>> {
+        let namespace =
+            namespace_or_default(client, namespace).context(NoNamespaceForSelectorSnafu {
+                selector: selector.clone(),
+            })?;
+
+        let buckets = client
+            .list_with_label_selector::<S3Bucket>(namespace, selector)
+            .await
+            .context(ListS3BucketsSnafu { namespace })?;
+
+        resolve_concurrently(&buckets, |bucket| async move {
+            bucket
+                .spec
+                .inlined(client, namespace)
+                .await
+                .map(|inlined| (bucket.name_any(), inlined))
+                .context(InlineS3BucketSnafu {
+                    resource_name: bucket.name_any(),
+                })
+        })
+        .await
+    }
+
+    /// Map &self to an [InlinedS3BucketSpec] by obtaining connection spec from the K8S API service if necessary.
+    ///
+    /// The resolved connection is [validated](S3ConnectionSpec::validate), so that a port or TLS
+    /// without a host as well as port 0 are rejected right away instead of silently producing no
+    /// (or a broken) endpoint.
+    pub async fn inlined(&self, client: &Client, namespace: &str) -> Result<InlinedS3BucketSpec> {
+        self.inlined_with(&mut S3ConnectionResolver::new(), client, namespace)
+            .await
+    }
+
+    /// Like [S3BucketSpec::inlined], but a referenced `S3Connection` is looked up using the
+    /// `resolver`, see [S3ConnectionResolver].
+    pub async fn inlined_with(
+        &self,
+        resolver: &mut S3ConnectionResolver,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<InlinedS3BucketSpec> {
+        match self.connection.as_ref() {
+            Some(connection_def) => {
+                let connection = connection_def
+                    .resolve_with(resolver, client, namespace)
+                    .await?;
+                connection.validate()?;
+
+                Ok(InlinedS3BucketSpec {
+                    connection: Some(connection),
+                    bucket_name: self.bucket_name.clone(),
+                    connection_name: self.connection_name(),
+                    base_path: self.base_path.clone(),
+                })
+            }
+            None => Ok(InlinedS3BucketSpec {
+                bucket_name: self.bucket_name.clone(),
+                connection: None,
+                connection_name: self.connection_name(),
+                base_path: self.base_path.clone(),
+            }),
+        }
+    }
+
+    /// Returns the [S3BucketSpec::connection_name] if set, otherwise the name of the referenced
+    /// `S3Connection` (if any).
+    fn connection_name(&self) -> Option<String> {
+        match (&self.connection_name, &self.connection) {
+            (Some(connection_name), _) => Some(connection_name.clone()),
+            (None, Some(S3ConnectionDef::Reference(reference))) => Some(reference.clone()),
+            (None, _) => None,
+        }
+    }
+}
+
+impl S3Bucket {
+    /// Returns the [`GroupVersionKind`] of the `S3Bucket` CRD, e.g. to set up watches or owner
+    /// references.
+    pub fn gvk() -> GroupVersionKind {
+        GroupVersionKind::gvk(S3_API_GROUP, S3_API_VERSION, "S3Bucket")
+    }
+
+    /// Convenience function to retrieve a S3 bucket resource from the K8S API service. Unlike
+    /// [`S3BucketSpec::get`], the metadata (like the UID or labels) is retained, which is needed
+    /// to set owner references or to detect drift.
+    pub async fn get_resource(
+        resource_name: &str,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3Bucket> {
+        client
+            .get::<S3Bucket>(resource_name, namespace)
+            .await
+            .context(MissingS3BucketSnafu {
+                resource_name,
+                namespace,
+            })
+    }
+
+    /// Converts the bucket into a [`DynamicObject`], which can be used by generic tooling and
+    /// reconcilers not knowing about the concrete type. Metadata and spec are preserved, so that
+    /// [`S3Bucket::from_dynamic_object`] returns an identical bucket.
+    pub fn to_dynamic_object(&self) -> Result<DynamicObject> {
+        serde_json::to_value(self)
+            .and_then(serde_json::from_value)
+            .context(S3BucketToDynamicObjectSnafu {
+                resource_name: self.name_any(),
+            })
+    }
+
+    /// Converts a [`DynamicObject`] back into a bucket. Returns an error if the object is not of
+    /// type `S3Bucket` or if the data of the object doesn't represent a valid bucket.
+    pub fn from_dynamic_object(object: DynamicObject) -> Result<S3Bucket> {
+        let resource_name = object.name_any();
+
+        match &object.types {
+            Some(types)
+                if types.api_version == S3Bucket::api_version(&())
+                    && types.kind == S3Bucket::kind(&()) => {}
+            types => {
+                let (api_version, kind) = types
+                    .as_ref()
+                    .map(|t| (t.api_version.clone(), t.kind.clone()))
+                    .unwrap_or_default();
+
+                return UnexpectedDynamicObjectTypeSnafu {
+                    resource_name,
+                    api_version,
+                    kind,
+                }
+                .fail();
+            }
+        }
+
+        serde_json::to_value(object)
+            .and_then(serde_json::from_value)
+            .context(S3BucketFromDynamicObjectSnafu { resource_name })
+    }
+
+    /// Returns the `s3.stackable.tech/owned-by=<bucket-name>` label, which operators should add to
+    /// resources created for this bucket to aid cleanup and selection. Names not being valid label
+    /// values (e.g. because they are longer than 63 characters) are sanitized.
+    pub fn owned_by_label(&self) -> Result<Label, LabelError> {
+        Label::try_from((
+            OWNED_BY_LABEL_KEY,
+            sanitize_label_value(&self.name_any()).as_str(),
+        ))
+    }
+
+    /// Returns a [`LabelSelector`] matching all resources labeled with the
+    /// [owned-by label](Self::owned_by_label) of this bucket.
+    pub fn owned_by_selector(&self) -> LabelSelector {
+        LabelSelector {
+            match_expressions: None,
+            match_labels: Some(BTreeMap::from([(
+                OWNED_BY_LABEL_KEY.to_string(),
+                sanitize_label_value(&self.name_any()),
+            )])),
+        }
+    }
+}
+
+/// Sanitizes the `value` (like a bucket name or host) to be a valid label (or annotation) value.
+///
+/// Characters other than alphanumerics, `-`, `_` and `.` are replaced by `-` and the value must
+/// start and end with an alphanumeric character. Values longer than 63 characters are truncated
+/// and suffixed with a hash of the original value, so that distinct long values (most likely)
+/// don't collide after truncation. The hash is deterministic across operator versions, which is
+/// required for labels used in selectors. Valid values are returned unchanged.
+pub fn sanitize_label_value(value: &str) -> String {
+    let sanitized = value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '-',
+        })
+        .collect::<String>();
+    let sanitized = trim_non_alphanumeric(&sanitized);
+
+    if sanitized.len() <= LABEL_VALUE_MAX_LEN {
+        return sanitized.to_string();
+    }
+
+    // The suffix consists of a `-` and 8 hexadecimal digits
+    let hash = format!("{:08x}", fnv1a_32(value.as_bytes()));
+    let prefix = trim_non_alphanumeric(&sanitized[..LABEL_VALUE_MAX_LEN - hash.len() - 1]);
+
+    format!("{prefix}-{hash}")
+}
+
+fn trim_non_alphanumeric(value: &str) -> &str {
+    value.trim_matches(|c: char| !c.is_ascii_alphanumeric())
+}
+
+/// The 32 bit FNV-1a hash, which is (contrary to the [`std::hash::DefaultHasher`]) guaranteed to
+/// be stable, see <http://www.isthe.com/chongo/tech/comp/fnv/>.
+fn fnv1a_32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x01000193)
+    })
+}
+
+/// Convenience struct with the connection spec inlined.
+pub struct InlinedS3BucketSpec {
+    pub bucket_name: Option<String>,
+    pub connection: Option<S3ConnectionSpec>,
+
+    /// The name of the connection for status reporting and event correlation, see
+    /// [S3BucketSpec::connection_name].
+    pub connection_name: Option<String>,
+
+    /// The path within the bucket, under which all data is stored, see [S3BucketSpec::base_path]
+    /// and [InlinedS3BucketSpec::full_path].
+    pub base_path: Option<String>,
+}
+
+impl InlinedS3BucketSpec {
+    /// Returns a builder for an [InlinedS3BucketSpec], see [InlinedS3BucketSpecBuilder].
+    pub fn builder() -> InlinedS3BucketSpecBuilder {
+        InlinedS3BucketSpecBuilder::default()
+    }
+
+    /// Build the endpoint URL from [S3ConnectionSpec::host] and [S3ConnectionSpec::port] and the S3 implementation to use.
+    /// The scheme is `https` if [S3ConnectionSpec::tls] is configured and `http` otherwise. Contrary
+    /// to [S3ConnectionSpec::endpoint], the port is always included, defaulting to the
+    /// [S3ConnectionSpec::effective_port].
+    pub fn endpoint(&self) -> Option<String> {
+        self.connection
+            .as_ref()
+            .and_then(|connection| connection.endpoint_with_port(Some(connection.effective_port())))
+    }
+
+    /// Like [InlinedS3BucketSpec::endpoint], but parses the endpoint into a [Url], which gives
+    /// access to the individual components like the scheme, host and port. Returns an error if
+    /// the endpoint is not a valid URL, e.g. because the host contains invalid characters.
+    pub fn endpoint_url(&self) -> Result<Option<Url>> {
+        self.endpoint()
+            .map(|endpoint| Url::parse(&endpoint).context(InvalidEndpointUrlSnafu { endpoint }))
+            .transpose()
+    }
+
+    /// Like [InlinedS3BucketSpec::endpoint], but returns an error describing why no (consistent)
+    /// endpoint can be built, see [S3ConnectionSpec::endpoint_strict].
+    pub fn endpoint_strict(&self) -> Result<String> {
+        self.connection
+            .as_ref()
+            .context(MissingConnectionSnafu)?
+            .endpoint_strict()
+    }
+
+    /// Returns the path of the object `key` within the bucket, which is the `key` prefixed with
+    /// the [base path](InlinedS3BucketSpec::base_path). The base path and the key are joined by
+    /// exactly one `/`. The `key` is returned unchanged if the base path is not set or empty.
+    pub fn full_path(&self, key: &str) -> String {
+        let base_path = self
+            .base_path
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+
+        if base_path.is_empty() {
+            return key.to_owned();
+        }
+
+        let key = key.trim_start_matches('/');
+        format!("{base_path}/{key}")
+    }
+
+    /// Returns the [S3ConnectionSpec::region] of the connection.
+    pub fn region(&self) -> Option<&str> {
+        self.connection.as_ref()?.region.as_deref()
+    }
+
+    /// Returns the access style to use for the connection, see
+    /// [S3ConnectionSpec::access_style_or_default]. [S3AccessStyle::Path] is returned if the
+    /// bucket has no connection.
+    pub fn access_style(&self) -> S3AccessStyle {
+        self.connection
+            .as_ref()
+            .map(S3ConnectionSpec::access_style_or_default)
+            .unwrap_or(S3AccessStyle::Path)
+    }
+
+    /// Whether the connection uses TLS. This is `false` if the bucket has no connection.
+    pub fn tls_enabled(&self) -> bool {
+        self.connection
+            .as_ref()
+            .is_some_and(|connection| connection.tls.is_some())
+    }
+
+    /// Returns how the certificate of the S3 server is verified, see [S3TlsVerification]. This
+    /// tells operators whether a CA bundle needs to be mounted.
+    pub fn tls_verification(&self) -> S3TlsVerification<'_> {
+        let Some(tls) = self
+            .connection
+            .as_ref()
+            .and_then(|connection| connection.tls.as_ref())
+        else {
+            return S3TlsVerification::Disabled;
+        };
+
+        match &tls.verification {
+            TlsVerification::None {} => S3TlsVerification::Insecure,
+            TlsVerification::Server(TlsServerVerification {
+                ca_cert: CaCert::WebPki {},
+            }) => S3TlsVerification::SystemTrustStore,
+            TlsVerification::Server(TlsServerVerification {
+                ca_cert: CaCert::SecretClass(secret_class),
+            }) => S3TlsVerification::SecretClass(secret_class),
+        }
+    }
+
+    /// Returns the `host:port` of the connection without any scheme, see [S3ConnectionSpec::host_port]
+    pub fn host_port(&self) -> Option<String> {
+        self.connection
+            .as_ref()
+            .and_then(|connection| connection.host_port())
+    }
+
+    /// Adds the volume containing the credentials of the connection (if any) to the `pod_builder`
+    /// and mounts it in all `container_builders` (e.g. init and main container), see
+    /// [InlinedS3BucketSpec::credentials_mount_paths] for the paths of the mounted files.
+    pub fn add_credentials_volumes_and_mounts(
+        &self,
+        pod_builder: &mut PodBuilder,
+        container_builders: Vec<&mut ContainerBuilder>,
+    ) -> Result<()> {
+        let (volumes, mounts) = self.credentials_volumes_and_mounts()?;
+        pod_builder.add_volumes(volumes);
+
+        for cb in container_builders {
+            cb.add_volume_mounts(mounts.clone());
+        }
+
+        Ok(())
+    }
+
+    /// It is recommended to use [InlinedS3BucketSpec::add_credentials_volumes_and_mounts], this
+    /// function returns the volumes and mounts in case you need to add them by yourself. Both are
+    /// empty if the connection has no credentials.
+    pub fn credentials_volumes_and_mounts(&self) -> Result<(Vec<Volume>, Vec<VolumeMount>)> {
+        let mut volumes = Vec::new();
+        let mut mounts = Vec::new();
+
+        if let Some(credentials) = self.credentials() {
+            let secret_class = &credentials.secret_class;
+            let volume_name = format!("{secret_class}-s3-credentials");
+
+            volumes.push(
+                credentials
+                    .to_volume(&volume_name)
+                    .context(CredentialsVolumeSnafu)?,
+            );
+            mounts.push(
+                VolumeMountBuilder::new(volume_name, format!("{SECRET_BASE_PATH}/{secret_class}"))
+                    .build(),
+            );
+        }
+
+        Ok((volumes, mounts))
+    }
+
+    /// Returns the paths of the files containing the access key and the secret key, which are
+    /// mounted by [InlinedS3BucketSpec::add_credentials_volumes_and_mounts].
+    /// This will be [None] if the connection has no credentials.
+    pub fn credentials_mount_paths(&self) -> Option<(String, String)> {
+        self.credentials().map(|credentials| {
+            let secret_class = &credentials.secret_class;
+            (
+                format!("{SECRET_BASE_PATH}/{secret_class}/{ACCESS_KEY_ID}"),
+                format!("{SECRET_BASE_PATH}/{secret_class}/{SECRET_ACCESS_KEY}"),
+            )
+        })
+    }
+
+    /// Returns the environment variables [ACCESS_KEY_FILE_ENV] and [SECRET_KEY_FILE_ENV]
+    /// pointing to the files mounted by [InlinedS3BucketSpec::add_credentials_volumes_and_mounts],
+    /// see [InlinedS3BucketSpec::credentials_mount_paths]. This will be empty if the connection
+    /// has no credentials.
+    ///
+    /// The credentials are provisioned by the secret-operator as files of an ephemeral volume,
+    /// there is no Secret the variables could reference via `secretKeyRef`. Products therefore
+    /// have to read the keys from the files the variables point to.
+    pub fn credentials_env_vars(&self) -> Vec<EnvVar> {
+        let Some((access_key_path, secret_key_path)) = self.credentials_mount_paths() else {
+            return Vec::new();
+        };
+
+        vec![
+            EnvVar {
+                name: ACCESS_KEY_FILE_ENV.to_owned(),
+                value: Some(access_key_path),
+                ..Default::default()
+            },
+            EnvVar {
+                name: SECRET_KEY_FILE_ENV.to_owned(),
+                value: Some(secret_key_path),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn credentials(&self) -> Option<&SecretClassVolume> {
+        self.connection.as_ref()?.credentials.as_ref()
+    }
+
+    /// Returns the volume attributes the secret-operator expects for the credentials
+    /// [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass) of the connection,
+    /// like `secrets.stackable.tech/class` and `secrets.stackable.tech/scope`. This is useful when
+    /// building volumes by hand, otherwise use [SecretClassVolume::to_volume].
+    ///
+    /// Returns [None] if the bucket has no connection, the connection has no credentials or the
+    /// credentials can't be expressed as valid attributes.
+    pub fn secret_csi_attributes(&self) -> Option<BTreeMap<String, String>> {
+        let credentials = self.connection.as_ref()?.credentials.as_ref()?;
+
+        credentials
+            .to_ephemeral_volume_source()
+            .ok()?
+            .volume_claim_template?
+            .metadata?
+            .annotations
+    }
+
+    /// Returns a concise, human-readable description of this bucket, which can be used in
+    /// Kubernetes events, like `bucket "data" on endpoint https://minio:9000 (tls)`. The endpoint
+    /// is the one returned by [InlinedS3BucketSpec::endpoint], so it includes the default port.
+    ///
+    /// The description doesn't contain any secret material, as credentials are only referenced
+    /// by the SecretClass providing them and not included at all.
+    pub fn event_message(&self) -> String {
+        let bucket = match &self.bucket_name {
+            Some(bucket_name) => format!("bucket {bucket_name:?}"),
+            None => "unnamed bucket".to_string(),
+        };
+
+        match &self.connection {
+            Some(connection) => {
+                let endpoint = self.endpoint().unwrap_or_else(|| "<unknown>".to_string());
+                let tls = match connection.tls {
+                    Some(_) => "tls",
+                    None => "no tls",
+                };
+
+                format!("{bucket} on endpoint {endpoint} ({tls})")
+            }
+            None => format!("{bucket} without connection"),
+        }
+    }
+
+    /// Returns an error if the bucket has a bucket name, but no connection, see
+    /// [S3BucketDef::resolve].
+    fn ensure_connection_configured(self) -> Result<Self> {
+        if let (Some(bucket_name), None) = (&self.bucket_name, &self.connection) {
+            return NoS3ConnectionConfiguredSnafu { bucket_name }.fail();
+        }
+
+        Ok(self)
+    }
+
+    /// Checks that this bucket satisfies the `requirements` of a product and returns an error
+    /// describing the first unmet requirement otherwise.
+    pub fn validate_requirements(&self, requirements: &RequirementSet) -> Result<()> {
+        ensure!(
+            !requirements.requires_bucket || self.bucket_name.is_some(),
+            BucketNameRequiredSnafu
+        );
+        ensure!(
+            !requirements.requires_region
+                || self
+                    .connection
+                    .as_ref()
+                    .is_some_and(|connection| connection.region.is_some()),
+            RegionRequiredSnafu
+        );
+        ensure!(
+            !requirements.requires_tls
+                || self
+                    .connection
+                    .as_ref()
+                    .is_some_and(|connection| connection.tls.is_some()),
+            TlsRequiredSnafu
+        );
+
+        Ok(())
+    }
+}
+
+/// The (minimum) requirements a product has regarding the S3 buckets it accesses, see
+/// [InlinedS3BucketSpec::validate_requirements].
+///
+/// Every operator declares the requirements of its product, e.g. Trino requires a region, whereas
+/// Spark doesn't.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RequirementSet {
+    /// The region of the connection must be set.
+    pub requires_region: bool,
+
+    /// The bucket name must be set.
+    pub requires_bucket: bool,
+
+    /// The connection must use TLS.
+    pub requires_tls: bool,
+}
+
+/// An S3 bucket definition, it can either be a reference to an explicit S3Bucket object,
+/// or it can be an inline definition of a bucket. Read the
+/// [S3 resources concept documentation](DOCS_BASE_URL_PLACEHOLDER/concepts/s3)
+/// to learn more.
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum S3BucketDef {
+    /// An inline definition, containing the S3 bucket properties.
+    Inline(S3BucketSpec),
+    /// A reference to an S3 bucket object. This is simply the name of the `S3Bucket`
+    /// resource.
+    Reference(String),
+}
+
+impl S3BucketDef {
+    /// Returns an [InlinedS3BucketSpec].
+    ///
+    /// A bucket with a bucket name is only usable with a connection, which is why such a bucket
+    /// without a connection is rejected with [Error::NoS3ConnectionConfigured]. Buckets specifying
+    /// neither a name nor a connection are resolved as is.
+    pub async fn resolve(&self, client: &Client, namespace: &str) -> Result<InlinedS3BucketSpec> {
+        self.resolve_with(&mut S3ConnectionResolver::new(), client, namespace)
+            .await
+    }
+
+    /// Like [S3BucketDef::resolve], but referenced `S3Connection`s are looked up using the
+    /// `resolver`. Passing the same resolver for all buckets of a reconcile fetches every
+    /// `S3Connection` only once, even if it's referenced by many buckets.
+    pub async fn resolve_with(
+        &self,
+        resolver: &mut S3ConnectionResolver,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<InlinedS3BucketSpec> {
+        let bucket = match self {
+            S3BucketDef::Inline(s3_bucket) => {
+                s3_bucket.inlined_with(resolver, client, namespace).await?
+            }
+            S3BucketDef::Reference(s3_bucket) => {
+                S3BucketSpec::get(s3_bucket.as_str(), client, namespace)
+                    .await?
+                    .inlined_with(resolver, client, namespace)
+                    .await?
+            }
+        };
+
+        bucket.ensure_connection_configured()
+    }
+
+    /// Resolves all `defs` concurrently (see [S3BucketDef::resolve]) and returns the
+    /// [InlinedS3BucketSpec]s in the order of the `defs`. Fails with the first error if resolving
+    /// any of the definitions fails.
+    pub async fn resolve_all(
+        defs: &[S3BucketDef],
+        client: &Client,
+        namespace: &str,
+    ) -> Result<Vec<InlinedS3BucketSpec>> {
+        resolve_concurrently(defs, |def| def.resolve(client, namespace)).await
+    }
+
+    /// Returns an [InlinedS3BucketSpec], looking up referenced objects in the namespace
+    /// determined by the `resolver` for the `owner` (usually the metadata of the custom resource
+    /// containing this definition).
+    pub async fn resolve_for(
+        &self,
+        client: &Client,
+        owner: &ObjectMeta,
+        resolver: &(impl NamespaceResolver + ?Sized),
+    ) -> Result<InlinedS3BucketSpec> {
+        let namespace = namespace::resolve_namespace(resolver, owner)?;
+        self.resolve(client, &namespace).await
+    }
+}
+
+/// Returns `namespace` or, if it is not given, the default namespace of the `client`. S3 objects
+/// are namespaced, which is why an empty namespace (which queries the cluster scope) is never
+/// returned.
+fn namespace_or_default<'a>(client: &'a Client, namespace: Option<&'a str>) -> Option<&'a str> {
+    Some(namespace.unwrap_or(&client.default_namespace)).filter(|namespace| !namespace.is_empty())
+}
+
+/// Calls `resolve` for all `items` concurrently and returns the results in the order of the
+/// `items`, independent of the order in which they complete.
+async fn resolve_concurrently<'a, T, U, F, Fut>(items: &'a [T], resolve: F) -> Result<Vec<U>>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future<Output = Result<U>>,
+{
+    futures::future::try_join_all(items.iter().map(resolve)).await
+}
+
+/// Returns the distinct names of all [SecretClasses](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass)
+/// needed to access the buckets defined by `defs`, e.g. to plan the volumes or RBAC rules of a
+/// workload. This includes the SecretClasses providing the credentials and the CA certificates of
+/// verified TLS connections.
+///
+/// Identical definitions (like multiple references to the same `S3Bucket`) are only resolved once
+/// and all definitions share a [S3ConnectionResolver], so every referenced `S3Connection` is only
+/// fetched once as well. This minimizes the number of API calls.
+pub async fn collect_secret_classes(
+    defs: &[S3BucketDef],
+    client: &Client,
+    namespace: &str,
+) -> Result<BTreeSet<String>> {
+    let mut resolver = S3ConnectionResolver::new();
+    let mut buckets = Vec::new();
+    for def in unique_bucket_defs(defs) {
+        buckets.push(def.resolve_with(&mut resolver, client, namespace).await?);
+    }
+
+    Ok(secret_classes(&buckets))
+}
+
+/// Returns the bucket definitions in `defs` without duplicates, preserving their order.
+fn unique_bucket_defs(defs: &[S3BucketDef]) -> Vec<&S3BucketDef> {
+    let mut unique_defs = Vec::new();
+    for def in defs {
+        if !unique_defs.contains(&def) {
+            unique_defs.push(def);
+        }
+    }
+    unique_defs
+}
+
+/// Returns the distinct names of the SecretClasses used by the connections of the `buckets`.
+fn secret_classes(buckets: &[InlinedS3BucketSpec]) -> BTreeSet<String> {
+    buckets
+        .iter()
+        .filter_map(|bucket| bucket.connection.as_ref())
+        .flat_map(|connection| {
+            let credentials = connection
+                .credentials
+                .as_ref()
+                .map(|credentials| credentials.secret_class.clone());
+            let ca_cert = match &connection.tls {
+                Some(Tls {
+                    verification:
+                        TlsVerification::Server(TlsServerVerification {
+                            ca_cert: CaCert::SecretClass(secret_class),
+                        }),
+                }) => Some(secret_class.clone()),
+                _ => None,
+            };
+
+            credentials.into_iter().chain(ca_cert)
+        })
+        .collect()
+}
+
+/// Operators are expected to define fields for this type in order to work with S3 connections.
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum S3ConnectionDef {
+    /// Inline definition of an S3 connection.
+    #[schemars(schema_with = "s3_connection_spec_schema")]
+    Inline(S3ConnectionSpec),
+    /// A reference to an S3Connection resource.
+    Reference(String),
+    /// A reference to a ConfigMap containing the S3 connection, see
+    /// [`S3ConnectionSpec::from_config_map_data`] for the supported keys.
+    ConfigMapReference(String),
+}
+
+/// A builder to build [InlinedS3BucketSpec] objects, which is mostly useful in tests and helpers.
+///
+/// ```
+/// use stackable_operator::commons::s3::{InlinedS3BucketSpec, S3ConnectionSpec};
+///
+/// let bucket = InlinedS3BucketSpec::builder()
+///     .bucket_name("my-bucket")
+///     .connection(S3ConnectionSpec {
+///         host: Some("minio".to_owned()),
+///         port: Some(9000),
+///         ..Default::default()
+///     })
+///     .build();
+///
+/// assert_eq!(bucket.bucket_name.as_deref(), Some("my-bucket"));
+/// assert_eq!(bucket.endpoint().as_deref(), Some("http://minio:9000"));
+/// ```
+#[derive(Clone, Default)]
+pub struct InlinedS3BucketSpecBuilder {
+    bucket_name: Option<String>,
+    connection: Option<S3ConnectionSpec>,
+    connection_name: Option<String>,
+    base_path: Option<String>,
+}
+
+impl InlinedS3BucketSpecBuilder {
+    pub fn new() -> InlinedS3BucketSpecBuilder {
+        InlinedS3BucketSpecBuilder::default()
+    }
+
+    pub fn bucket_name(&mut self, bucket_name: impl Into<String>) -> &mut Self {
+        self.bucket_name = Some(bucket_name.into());
+        self
+    }
+
+    pub fn bucket_name_opt(&mut self, bucket_name: impl Into<Option<String>>) -> &mut Self {
+        self.bucket_name = bucket_name.into();
+        self
+    }
+
+    pub fn connection(&mut self, connection: S3ConnectionSpec) -> &mut Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    pub fn connection_opt(&mut self, connection: impl Into<Option<S3ConnectionSpec>>) -> &mut Self {
+        self.connection = connection.into();
+        self
+    }
+
+    pub fn connection_name(&mut self, connection_name: impl Into<String>) -> &mut Self {
+        self.connection_name = Some(connection_name.into());
+        self
+    }
+
+    pub fn connection_name_opt(&mut self, connection_name: impl Into<Option<String>>) -> &mut Self {
+        self.connection_name = connection_name.into();
+        self
+    }
+
+    pub fn base_path(&mut self, base_path: impl Into<String>) -> &mut Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
+
+    pub fn build(&self) -> InlinedS3BucketSpec {
+        InlinedS3BucketSpec {
+            bucket_name: self.bucket_name.clone(),
+            connection: self.connection.clone(),
+            connection_name: self.connection_name.clone(),
+            base_path: self.base_path.clone(),
+        }
+    }
+}
+
+/// A builder to build [S3ConnectionSpec] objects, which is mostly useful in tests and helpers.
+/// Fields which are not set are left as [None].
+///
+/// ```
+/// use stackable_operator::commons::s3::S3ConnectionSpec;
+///
+/// let connection = S3ConnectionSpec::builder()
+///     .host("minio")
+///     .port(9000)
+///     .secret_class("minio-credentials")
+///     .build();
+///
+/// assert_eq!(connection.endpoint().as_deref(), Some("http://minio:9000"));
+/// ```
+#[derive(Clone, Default)]
+pub struct S3ConnectionSpecBuilder {
+    connection: S3ConnectionSpec,
+}
+
+impl S3ConnectionSpecBuilder {
+    pub fn new() -> S3ConnectionSpecBuilder {
+        S3ConnectionSpecBuilder::default()
+    }
+
+    pub fn host(&mut self, host: impl Into<String>) -> &mut Self {
+        self.connection.host = Some(host.into());
+        self
+    }
+
+    pub fn port(&mut self, port: u16) -> &mut Self {
+        self.connection.port = Some(port);
+        self
+    }
+
+    /// Sets the [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass) providing
+    /// the credentials of the connection.
+    pub fn secret_class(&mut self, secret_class: impl Into<String>) -> &mut Self {
+        self.connection.credentials = Some(SecretClassVolume::new(secret_class.into(), None));
+        self
+    }
+
+    pub fn tls(&mut self, tls: Tls) -> &mut Self {
+        self.connection.tls = Some(tls);
+        self
+    }
+
+    pub fn region(&mut self, region: impl Into<String>) -> &mut Self {
+        self.connection.region = Some(region.into());
+        self
+    }
+
+    pub fn access_style(&mut self, access_style: S3AccessStyle) -> &mut Self {
+        self.connection.access_style = Some(access_style);
+        self
+    }
+
+    pub fn build(&self) -> S3ConnectionSpec {
+        self.connection.clone()
+    }
+}
+
+impl S3ConnectionDef {
+    /// Returns an [S3ConnectionSpec].
+    pub async fn resolve(&self, client: &Client, namespace: &str) -> Result<S3ConnectionSpec> {
+        self.resolve_with(&mut S3ConnectionResolver::new(), client, namespace)
+            .await
+    }
+
+    /// Like [S3ConnectionDef::resolve], but referenced `S3Connection`s are looked up using the
+    /// `resolver`, which only fetches every connection once.
+    pub async fn resolve_with(
+        &self,
+        resolver: &mut S3ConnectionResolver,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3ConnectionSpec> {
+        match self {
+            S3ConnectionDef::Inline(s3_connection_spec) => Ok(s3_connection_spec.clone()),
+            S3ConnectionDef::Reference(s3_conn_reference) => {
+                resolver.get(s3_conn_reference, client, namespace).await
+            }
+            S3ConnectionDef::ConfigMapReference(config_map_name) => {
+                let config_map = client
+                    .get::<ConfigMap>(config_map_name, namespace)
+                    .await
+                    .context(MissingS3ConnectionConfigMapSnafu {
+                        resource_name: config_map_name,
+                        namespace,
+                    })?;
+                S3ConnectionSpec::from_config_map_data(
+                    config_map_name,
+                    &config_map.data.unwrap_or_default(),
+                )
+            }
+        }
+    }
+
+    /// Returns an [S3ConnectionSpec], looking up referenced objects in the namespace determined
+    /// by the `resolver` for the `owner`, see [S3BucketDef::resolve_for].
+    pub async fn resolve_for(
+        &self,
+        client: &Client,
+        owner: &ObjectMeta,
+        resolver: &(impl NamespaceResolver + ?Sized),
+    ) -> Result<S3ConnectionSpec> {
+        let namespace = namespace::resolve_namespace(resolver, owner)?;
+        self.resolve(client, &namespace).await
+    }
+}
+
+/// Fields set in `self` always win over the `defaults`. Fields identifying the server (`host`,
+/// `port` and `endpointOverride`) are merged as a whole: If any of them is set in `self`, none of
+/// them is taken from the `defaults`, because e.g. the default port doesn't necessarily apply to
+/// another host. The same applies to the `endpointSchemeOverride`, which is only taken from the
+/// `defaults` together with the server. All other fields are taken from the `defaults`
+/// individually, if unset.
+impl Merge for S3ConnectionSpec {
+    fn merge(&mut self, defaults: &Self) {
+        fn merge_field<T: Clone>(field: &mut Option<T>, default: &Option<T>) {
+            if field.is_none() {
+                field.clone_from(default);
+            }
+        }
+
+        let Self {
+            host,
+            port,
+            endpoint_override,
+            endpoint_scheme_override,
+            access_style,
+            region,
+            credentials,
+            tls,
+            multipart_threshold,
+            multipart_part_size,
+            request_payer,
+            max_retries,
+            connection_type,
+        } = self;
+
+        if host.is_none() && port.is_none() && endpoint_override.is_none() {
+            host.clone_from(&defaults.host);
+            port.clone_from(&defaults.port);
+            endpoint_override.clone_from(&defaults.endpoint_override);
+            merge_field(endpoint_scheme_override, &defaults.endpoint_scheme_override);
+        }
+
+        merge_field(access_style, &defaults.access_style);
+        merge_field(region, &defaults.region);
+        merge_field(credentials, &defaults.credentials);
+        merge_field(tls, &defaults.tls);
+        merge_field(multipart_threshold, &defaults.multipart_threshold);
+        merge_field(multipart_part_size, &defaults.multipart_part_size);
+        merge_field(request_payer, &defaults.request_payer);
+        merge_field(max_retries, &defaults.max_retries);
+        merge_field(connection_type, &defaults.connection_type);
+    }
+}
+
+/// S3 connection definition as a resource.
+/// Learn more on the [S3 concept documentation](DOCS_BASE_URL_PLACEHOLDER/concepts/s3).
+#[derive(
+    CustomResource, Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize,
+)]
+#[kube(
+    group = "s3.stackable.tech",
+    version = "v1alpha1",
+    kind = "S3Connection",
+    plural = "s3connections",
+    crates(
+        kube_core = "kube::core",
+        k8s_openapi = "k8s_openapi",
+        schemars = "schemars"
+    ),
+    namespaced,
+    // The schema is implemented manually to include the CEL validation rules.
+    schema = "manual"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ConnectionSpec {
+    /// Hostname of the S3 server without any protocol or port. For example: `west1.my-cloud.com`.
+    // FIXME: Try to remove the Option<>, as this field should be mandatory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    /// Port the S3 server listens on.
+    /// If not specified the product will determine the port to use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// The full endpoint URL of the S3 server, for example `https://s3.my-cloud.com:9000`.
+    /// If specified, it is used instead of the endpoint composed from `host`, `port` and `tls`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint_override: Option<String>,
+
+    /// The scheme of the endpoint, one of `http`, `https` or `s3a`. If specified, it is used
+    /// regardless of `tls`, e.g. if a proxy terminates TLS in front of the S3 server.
+    /// If not specified, the scheme is `https` if `tls` is configured and `http` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint_scheme_override: Option<String>,
+
+    // FIXME: Try to remove the Option<>, as this field should be mandatory
+    /// Which access style to use.
+    /// If not specified, AWS S3 uses virtual hosted-style access and all other servers use
+    /// path-style access, as self-hosted S3-compatible stores (like MinIO) usually require it.
+    /// Have a look at the [AWS documentation](https://docs.aws.amazon.com/AmazonS3/latest/userguide/VirtualHosting.html).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_style: Option<S3AccessStyle>,
+
+    /// The region of the S3 server, for example `eu-central-1`, which is used to sign requests.
+    /// If not specified the product will determine the region to use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// If the S3 uses authentication you have to specify you S3 credentials.
+    /// In the most cases a [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass)
+    /// providing `accessKey` and `secretKey` is sufficient.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<SecretClassVolume>,
+
+    /// If you want to use TLS when talking to S3 you can enable TLS encrypted communication with this setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<Tls>,
+
+    /// Objects larger than this size are uploaded in multiple parts, for example `64Mi`.
+    /// If not specified the product will determine the threshold to use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multipart_threshold: Option<MemoryQuantity>,
+
+    /// The size of the individual parts of a multipart upload, for example `64Mi`.
+    /// If not specified the product will determine the part size to use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multipart_part_size: Option<MemoryQuantity>,
+
+    /// Who pays for requests and data transfer, required for "requester pays" buckets.
+    /// Have a look at the [AWS documentation](https://docs.aws.amazon.com/AmazonS3/latest/userguide/RequesterPaysBuckets.html).
+    /// If not specified the product will use its default, which usually is the bucket owner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_payer: Option<RequestPayer>,
+
+    /// The maximum number of attempts of failed requests to the S3 server.
+    /// If not specified the product will determine the number of attempts to use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Whether the S3 server is genuine AWS S3 or a generic S3-compatible store (like MinIO).
+    /// This drives defaults, like the access style, if not configured explicitly.
+    /// If not specified, AWS S3 is detected by the host (`*.amazonaws.com`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_type: Option<ConnectionType>,
+}
+
+impl S3ConnectionSpec {
+    /// Returns a builder for an [S3ConnectionSpec], see [S3ConnectionSpecBuilder].
+    pub fn builder() -> S3ConnectionSpecBuilder {
+        S3ConnectionSpecBuilder::default()
+    }
+
+    /// Convenience function to retrieve the spec of a S3 connection resource from the K8S API service.
+    /// Use [`S3Connection::get_resource`] to retrieve the metadata as well.
+    pub async fn get(
+        resource_name: &str,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3ConnectionSpec> {
+        S3Connection::get_resource(resource_name, client, namespace)
+            .await
+            .map(|conn| conn.spec)
+    }
+
+    /// Convenience function to retrieve the spec of the single S3 connection
+    /// resource matching the label `selector` from the K8S API service.
+    ///
+    /// This is useful in deployments, which don't know the name of the
+    /// connection, but tag connections with a well-known label instead. It
+    /// returns an error if zero or multiple connections match the selector.
+    pub async fn get_by_label(
+        selector: &LabelSelector,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3ConnectionSpec> {
+        let connections = client
+            .list_with_label_selector::<S3Connection>(namespace, selector)
+            .await
+            .context(ListS3ConnectionsSnafu { namespace })?;
+
+        Self::single_selector_match(connections, selector, namespace)
+    }
+
+    /// Returns the spec of the single connection in `connections`, which is
+    /// the list of connections matching `selector`.
+    fn single_selector_match(
+        mut connections: Vec<S3Connection>,
+        selector: &LabelSelector,
+        namespace: &str,
+    ) -> Result<S3ConnectionSpec> {
+        match connections.len() {
+            0 => NoMatchingS3ConnectionSnafu {
+                selector: selector.clone(),
+                namespace,
+            }
+            .fail(),
+            1 => Ok(connections.remove(0).spec),
+            _ => AmbiguousS3ConnectionSnafu {
+                selector: selector.clone(),
+                namespace,
+                names: connections
+                    .iter()
+                    .map(|connection| connection.name_any())
+                    .collect::<Vec<_>>(),
+            }
+            .fail(),
+        }
+    }
+
+    /// Parses a connection from YAML, but rejects unknown fields, like a misspelled `hots`, with
+    /// [`Error::UnknownS3ConnectionField`] instead of silently ignoring them.
+    ///
+    /// Only the fields of the connection itself are checked, nested objects are parsed as usual.
+    /// Objects decoded by kube stay lenient, so that older operators accept newer resources.
+    pub fn from_strict_yaml(yaml: &str) -> Result<S3ConnectionSpec> {
+        let value: serde_yaml::Value =
+            serde_yaml::from_str(yaml).context(ParseS3ConnectionSnafu)?;
+
+        if let serde_yaml::Value::Mapping(mapping) = &value {
+            let expected = Self::field_names();
+
+            for key in mapping.keys() {
+                let field = match key.as_str() {
+                    Some(field) => field.to_owned(),
+                    None => format!("{key:?}"),
+                };
+
+                ensure!(
+                    expected.contains(&field),
+                    UnknownS3ConnectionFieldSnafu { field, expected }
+                );
+            }
+        }
+
+        serde_yaml::from_value(value).context(ParseS3ConnectionSnafu)
+    }
+
+    /// Returns the (serialized) names of all fields of a connection, as declared in its schema.
+    fn field_names() -> Vec<String> {
+        match Self::json_schema(&mut SchemaGenerator::default()) {
+            Schema::Object(SchemaObject {
+                object: Some(object),
+                ..
+            }) => object.properties.into_keys().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Builds a connection from the environment variables starting with `prefix`, which is
+    /// useful for local development without a Kubernetes cluster. The following variables are
+    /// read:
+    ///
+    /// - `{prefix}_HOST` (required), the hostname of the S3 server.
+    /// - `{prefix}_PORT`, the port of the S3 server.
+    /// - `{prefix}_REGION`, the region of the S3 server.
+    /// - `{prefix}_TLS`, either `true` or `false`. If `true`, TLS is used and the server is
+    ///   verified using the CA certificates trusted by the common web browsers.
+    pub fn from_env(prefix: &str) -> Result<S3ConnectionSpec> {
+        Self::from_env_vars(prefix, |name| std::env::var(name).ok())
+    }
+
+    /// Builds a connection from the variables starting with `prefix` returned by `lookup`, see
+    /// [`S3ConnectionSpec::from_env`].
+    fn from_env_vars(
+        prefix: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<S3ConnectionSpec> {
+        let env_var = |suffix: &str| {
+            let name = format!("{prefix}_{suffix}");
+            let value = lookup(&name);
+            (name, value)
+        };
+
+        let (name, host) = env_var("HOST");
+        let host = host.context(MissingEnvVarSnafu { name })?;
+
+        let port = match env_var("PORT") {
+            (name, Some(value)) => Some(
+                value
+                    .parse()
+                    .ok()
+                    .context(InvalidEnvVarSnafu { name, value })?,
+            ),
+            (_, None) => None,
+        };
+
+        let tls = match env_var("TLS") {
+            (name, Some(value)) => match value.parse() {
+                Ok(true) => Some(Tls {
+                    verification: TlsVerification::Server(TlsServerVerification {
+                        ca_cert: CaCert::WebPki {},
+                    }),
+                }),
+                Ok(false) => None,
+                Err(_) => return InvalidEnvVarSnafu { name, value }.fail(),
+            },
+            (_, None) => None,
+        };
+
+        Ok(S3ConnectionSpec {
+            host: Some(host),
+            port,
+            region: env_var("REGION").1,
+            tls,
+            ..S3ConnectionSpec::default()
+        })
+    }
+
+    /// Builds a connection from the `data` of the ConfigMap `config_map_name`, which is
+    /// referenced by [`S3ConnectionDef::ConfigMapReference`]. The following keys are read:
+    ///
+    /// - `host` (required), the hostname of the S3 server.
+    /// - `port`, the port of the S3 server.
+    /// - `secretClass`, the name of the SecretClass providing the credentials.
+    pub fn from_config_map_data(
+        config_map_name: &str,
+        data: &BTreeMap<String, String>,
+    ) -> Result<S3ConnectionSpec> {
+        let host = data.get("host").context(MissingConfigMapKeySnafu {
+            config_map: config_map_name,
+            key: "host",
+        })?;
+
+        let port = match data.get("port") {
+            Some(value) => Some(value.parse().ok().context(InvalidConfigMapKeySnafu {
+                config_map: config_map_name,
+                key: "port",
+                value,
+            })?),
+            None => None,
+        };
+
+        let credentials = data
+            .get("secretClass")
+            .map(|secret_class| SecretClassVolume::new(secret_class.clone(), None));
+
+        Ok(S3ConnectionSpec {
+            host: Some(host.clone()),
+            port,
+            credentials,
+            ..S3ConnectionSpec::default()
+        })
+    }
+
+    /// Returns the [S3ConnectionSpec::connection_type] if set. Otherwise, the type is derived from
+    /// the host: Hosts ending with `.amazonaws.com` are AWS S3. [None] is returned if the type
+    /// can't be determined, in which case no type specific defaults apply.
+    pub fn effective_connection_type(&self) -> Option<ConnectionType> {
+        if let Some(connection_type) = &self.connection_type {
+            return Some(connection_type.clone());
+        }
+
+        self.host
+            .as_deref()
+            .map(|host| host.trim_end_matches('.').to_ascii_lowercase())
+            .filter(|host| host == "amazonaws.com" || host.ends_with(".amazonaws.com"))
+            .map(|_| ConnectionType::Aws)
+    }
+
+    /// Returns the [S3ConnectionSpec::access_style] if set. Otherwise, the default access style of
+    /// the [effective connection type](Self::effective_connection_type) is returned, if any.
+    pub fn effective_access_style(&self) -> Option<S3AccessStyle> {
+        if let Some(access_style) = &self.access_style {
+            return Some(access_style.clone());
+        }
+
+        self.effective_connection_type()
+            .map(|connection_type| connection_type.default_access_style())
+    }
+
+    /// Returns the [effective access style](Self::effective_access_style) if it can be
+    /// determined, [S3AccessStyle::Path] otherwise. Path-style access is the safe default, as
+    /// self-hosted S3-compatible stores (like MinIO) usually require it.
+    ///
+    /// Note that this differs from [S3AccessStyle::default], which is kept as virtual
+    /// hosted-style for backwards compatibility.
+    pub fn access_style_or_default(&self) -> S3AccessStyle {
+        self.effective_access_style().unwrap_or(S3AccessStyle::Path)
+    }
+
+    /// Build the endpoint URL from this connection. The [S3ConnectionSpec::endpoint_override] is
+    /// returned as is if set.
+    pub fn endpoint(&self) -> Option<String> {
+        self.endpoint_with_port(self.port)
+    }
+
+    fn endpoint_with_port(&self, port: Option<u16>) -> Option<String> {
+        if let Some(endpoint_override) = &self.endpoint_override {
+            return Some(endpoint_override.clone());
+        }
+
+        let protocol = self.endpoint_scheme();
+        self.host.as_ref().map(|h| match port {
+            Some(p) => format!("{protocol}://{h}:{p}"),
+            None => format!("{protocol}://{h}"),
+        })
+    }
+
+    /// Returns the [S3ConnectionSpec::endpoint_scheme_override] if set. Otherwise, the scheme is
+    /// derived from TLS, which is `https` if [S3ConnectionSpec::tls] is configured and `http` if
+    /// not. The override is returned as is, use [S3ConnectionSpec::endpoint_strict] to ensure it
+    /// is one of [ENDPOINT_SCHEMES].
+    pub fn endpoint_scheme(&self) -> &str {
+        match (self.endpoint_scheme_override.as_deref(), &self.tls) {
+            (Some(scheme), _) => scheme,
+            (None, Some(_tls)) => "https",
+            (None, None) => "http",
+        }
+    }
+
+    /// Returns the [S3ConnectionSpec::port] if set. Otherwise, the default port of the protocol
+    /// is returned, which is 443 if [S3ConnectionSpec::tls] is configured and 80 if not.
+    pub fn effective_port(&self) -> u16 {
+        match (self.port, &self.tls) {
+            (Some(port), _) => port,
+            (None, Some(_tls)) => 443,
+            (None, None) => 80,
+        }
+    }
+
+    /// Merges the [`S3Connection`] `name` in `namespace` (usually named
+    /// [`DEFAULT_S3_CONNECTION_NAME`]) beneath this connection, so that it supplies the values of
+    /// all fields not set in this connection, see the [`Merge`] implementation. This connection is
+    /// returned as is if the default connection doesn't exist.
+    ///
+    /// ```no_run
+    /// # use stackable_operator::client::Client;
+    /// # use stackable_operator::commons::s3::{S3ConnectionDef, DEFAULT_S3_CONNECTION_NAME};
+    /// # async fn test(client: &Client, def: &S3ConnectionDef) -> Result<(), stackable_operator::commons::s3::Error> {
+    /// let connection = def
+    ///     .resolve(client, "default")
+    ///     .await?
+    ///     .with_cluster_default(client, DEFAULT_S3_CONNECTION_NAME, "stackable-operators")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_cluster_default(
+        mut self,
+        client: &Client,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Self> {
+        let default = client
+            .get_opt::<S3Connection>(name, namespace)
+            .await
+            .context(GetDefaultS3ConnectionSnafu {
+                resource_name: name,
+                namespace,
+            })?;
+
+        if let Some(default) = default {
+            self.merge(&default.spec);
+        }
+
+        Ok(self)
+    }
+
+    /// Returns an [Error::InvalidS3Connection] naming the offending field if the port is 0 (which
+    /// is never a valid S3 port), if the endpoint scheme override is not one of
+    /// [ENDPOINT_SCHEMES] or if a port or TLS is specified, but no host. Connections
+    /// without any of these fields (e.g. only specifying credentials, which are completed later)
+    /// as well as connections using the [S3ConnectionSpec::endpoint_override] are valid.
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.port != Some(0),
+            InvalidS3ConnectionSnafu {
+                field: "port",
+                reason: "0 is not a valid port"
+            }
+        );
+        self.validate_endpoint_scheme()?;
+
+        if self.host.is_some() || self.endpoint_override.is_some() {
+            return Ok(());
+        }
+
+        ensure!(
+            self.port.is_none(),
+            InvalidS3ConnectionSnafu {
+                field: "port",
+                reason: "a host is required"
+            }
+        );
+        ensure!(
+            self.tls.is_none(),
+            InvalidS3ConnectionSnafu {
+                field: "tls",
+                reason: "a host is required"
+            }
+        );
+
+        Ok(())
+    }
+
+    fn validate_endpoint_scheme(&self) -> Result<()> {
+        ensure!(
+            self.endpoint_scheme_override
+                .as_deref()
+                .map_or(true, |scheme| ENDPOINT_SCHEMES.contains(&scheme)),
+            InvalidS3ConnectionSnafu {
+                field: "endpointSchemeOverride",
+                reason: "the scheme must be one of http, https or s3a"
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Like [S3ConnectionSpec::endpoint], but returns an error instead of [None] or an endpoint,
+    /// which most likely doesn't work.
+    ///
+    /// Besides a missing host, this rejects the well-known ports of the other protocol, i.e. using
+    /// `https` (e.g. TLS) on port 80 or `http` on port 443, as well as an
+    /// [endpoint scheme override](S3ConnectionSpec::endpoint_scheme_override) not contained in
+    /// [ENDPOINT_SCHEMES]. The [S3ConnectionSpec::endpoint_override] is returned as is.
+    pub fn endpoint_strict(&self) -> Result<String> {
+        if let Some(endpoint_override) = &self.endpoint_override {
+            return Ok(endpoint_override.clone());
+        }
+
+        self.validate_endpoint_scheme()?;
+
+        ensure!(self.host.is_some(), MissingHostSnafu);
+
+        match (self.endpoint_scheme(), self.port) {
+            ("https", Some(port @ 80)) => TlsPortMismatchSnafu {
+                protocol: "https",
+                expected: "http",
+                port,
+            }
+            .fail(),
+            ("http", Some(port @ 443)) => TlsPortMismatchSnafu {
+                protocol: "http",
+                expected: "https",
+                port,
+            }
+            .fail(),
+            _ => self.endpoint().context(MissingHostSnafu),
+        }
+    }
+
+    /// Returns whether this connection and `other` point to the same S3 server.
+    ///
+    /// If both connections set an [endpoint override](S3ConnectionSpec::endpoint_override), only
+    /// the overrides are compared, because `host` and `port` are ignored in that case. If neither
+    /// connection sets an override, `host` and `port` are compared. A connection with an override
+    /// is never equivalent to a connection without one.
+    pub fn connection_equivalent(&self, other: &S3ConnectionSpec) -> bool {
+        match (&self.endpoint_override, &other.endpoint_override) {
+            (Some(endpoint_override), Some(other_endpoint_override)) => {
+                endpoint_override == other_endpoint_override
+            }
+            (None, None) => self.host == other.host && self.port == other.port,
+            _ => false,
+        }
+    }
+
+    /// Returns the (camelCase) names of the optional fields, which are set in this connection, like
+    /// `host` or `endpointOverride`. This is useful to log at a glance how a connection is
+    /// configured, without logging the values themselves.
+    pub fn configured_fields(&self) -> BTreeSet<&'static str> {
+        // Destructuring ensures that new fields are not forgotten here.
+        let Self {
+            host,
+            port,
+            endpoint_override,
+            endpoint_scheme_override,
+            access_style,
+            region,
+            credentials,
+            tls,
+            multipart_threshold,
+            multipart_part_size,
+            request_payer,
+            max_retries,
+            connection_type,
+        } = self;
+
+        [
+            ("host", host.is_some()),
+            ("port", port.is_some()),
+            ("endpointOverride", endpoint_override.is_some()),
+            ("endpointSchemeOverride", endpoint_scheme_override.is_some()),
+            ("accessStyle", access_style.is_some()),
+            ("region", region.is_some()),
+            ("credentials", credentials.is_some()),
+            ("tls", tls.is_some()),
+            ("multipartThreshold", multipart_threshold.is_some()),
+            ("multipartPartSize", multipart_part_size.is_some()),
+            ("requestPayer", request_payer.is_some()),
+            ("maxRetries", max_retries.is_some()),
+            ("connectionType", connection_type.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, is_set)| is_set.then_some(name))
+        .collect()
+    }
+
+    /// Build the bare `host:port` from [S3ConnectionSpec::host] and [S3ConnectionSpec::port]
+    /// without any scheme, as expected by some SDKs. IPv6 addresses are enclosed in brackets and
+    /// the port is omitted if not set.
+    pub fn host_port(&self) -> Option<String> {
+        self.host.as_ref().map(|host| {
+            let host = match host.parse::<Ipv6Addr>() {
+                Ok(_) => format!("[{host}]"),
+                Err(_) => host.clone(),
+            };
+
+            match self.port {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            }
+        })
+    }
+}
+
+impl S3Connection {
+    /// Returns the [`GroupVersionKind`] of the `S3Connection` CRD, e.g. to set up watches or
+    /// owner references.
+    pub fn gvk() -> GroupVersionKind {
+        GroupVersionKind::gvk(S3_API_GROUP, S3_API_VERSION, "S3Connection")
+    }
+
+    /// Convenience function to retrieve a S3 connection resource from the K8S API service.
+    /// Unlike [`S3ConnectionSpec::get`], the metadata (like the UID or labels) is retained, which
+    /// is needed to set owner references or to detect drift.
+    pub async fn get_resource(
+        resource_name: &str,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3Connection> {
+        client
+            .get::<S3Connection>(resource_name, namespace)
+            .await
+            .context(MissingS3ConnectionSnafu {
+                resource_name,
+                namespace,
+            })
+    }
+}
+
+/// Returns the CEL validation rules, which ensure constraints spanning multiple fields of the
+/// [`S3ConnectionSpec`]. These rules are validated by Kubernetes.
+///
+/// Currently, the following rules are declared:
+///
+/// - If `tls` is set, the `host` or `endpointOverride` must be set as well. The `port` is optional,
+///   it defaults to 443 with TLS (see [`S3ConnectionSpec::effective_port`]).
+fn s3_connection_validation_rules() -> Vec<ValidationRule> {
+    vec![ValidationRule {
+        rule: "!has(self.tls) || has(self.host) || has(self.endpointOverride)".to_owned(),
+        message: Some("host or endpointOverride must be set if tls is set".to_owned()),
+        ..ValidationRule::default()
+    }]
+}
+
+/// Returns the schema of the [`S3ConnectionSpec`], including the [CEL validation
+/// rules](s3_connection_validation_rules). Must be used (via `#[schemars(schema_with)]`) whenever
+/// the spec is embedded into other types.
+fn s3_connection_spec_schema(gen: &mut SchemaGenerator) -> Schema {
+    add_validation_rules(
+        S3ConnectionSpec::json_schema(gen),
+        s3_connection_validation_rules(),
+    )
+}
+
+impl JsonSchema for S3Connection {
+    fn schema_name() -> String {
+        "S3Connection".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // This mirrors the schema kube derives for custom resources, but uses the spec schema
+        // including the CEL validation rules.
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some("S3Connection".to_owned()),
+                description: Some(
+                    "Auto-generated derived type for S3ConnectionSpec via `CustomResource`"
+                        .to_owned(),
+                ),
+                ..Metadata::default()
+            })),
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                properties: [("spec".to_owned(), s3_connection_spec_schema(gen))].into(),
+                required: ["spec".to_owned()].into(),
+                ..ObjectValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+#[derive(
+    strum::Display, Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize,
+)]
+#[strum(serialize_all = "PascalCase")]
+pub enum S3AccessStyle {
+    /// Use path-style access as described in <https://docs.aws.amazon.com/AmazonS3/latest/userguide/VirtualHosting.html#path-style-access>
+    Path,
+    /// Use as virtual hosted-style access as described in <https://docs.aws.amazon.com/AmazonS3/latest/userguide/VirtualHosting.html#virtual-hosted-style-access>
+    #[default]
+    VirtualHosted,
+}
+
+#[derive(strum::Display, Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[strum(serialize_all = "PascalCase")]
+pub enum ConnectionType {
+    /// Genuine AWS S3, which uses virtual hosted-style access by default.
+    Aws,
+    /// A generic S3-compatible store (like MinIO), which uses path-style access by default, as
+    /// most of them don't support virtual hosted-style access out of the box.
+    S3Compatible,
+}
+
+impl ConnectionType {
+    /// Returns the access style used if [S3ConnectionSpec::access_style] is not set.
+    pub fn default_access_style(&self) -> S3AccessStyle {
+        match self {
+            ConnectionType::Aws => S3AccessStyle::VirtualHosted,
+            ConnectionType::S3Compatible => S3AccessStyle::Path,
+        }
+    }
+}
+
+/// How the certificate of the S3 server is verified, see [InlinedS3BucketSpec::tls_verification].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum S3TlsVerification<'a> {
+    /// TLS is not used at all (or the bucket has no connection).
+    Disabled,
+    /// TLS is used, but the certificate of the S3 server is not verified.
+    Insecure,
+    /// The certificate of the S3 server is verified using the CA certificates trusted by the
+    /// common web browsers.
+    SystemTrustStore,
+    /// The certificate of the S3 server is verified using the CA certificate provided by the
+    /// contained [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass), which
+    /// needs to be mounted.
+    SecretClass(&'a str),
+}
+
+#[derive(strum::Display, Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[strum(serialize_all = "PascalCase")]
+pub enum RequestPayer {
+    /// The bucket owner pays for requests and data transfer.
+    BucketOwner,
+    /// The requester pays for requests and data transfer, as required by "requester pays" buckets.
+    Requester,
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        convert::Infallible,
+        str,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex, PoisonError,
+        },
+    };
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+    use kube::{api::DynamicObject, CustomResourceExt, Resource, ResourceExt};
+    use rstest::rstest;
+
+    use crate::client::Client;
+    use crate::commons::s3::{
+        collect_secret_classes, fnv1a_32, resolve_concurrently, s3_rbac_policy_rules,
+        sanitize_label_value, secret_classes, unique_bucket_defs, BucketNameRequiredSnafu, Error,
+        RequestPayer, S3AccessStyle, S3BucketDef, S3ConnectionDef, S3TlsVerification,
+        LABEL_VALUE_MAX_LEN, OWNED_BY_LABEL_KEY, S3_API_GROUP, S3_API_VERSION,
+    };
+    use crate::commons::s3::{
+        InlinedS3BucketSpec, RequirementSet, S3Bucket, S3BucketSpec, S3Connection, S3ConnectionSpec,
+    };
+    use crate::commons::secret_class::SecretClassVolume;
+    use crate::config::merge::Merge;
+    use crate::yaml;
+
+    fn connection_with_host(name: &str, host: &str) -> S3Connection {
+        S3Connection::new(
+            name,
+            S3ConnectionSpec {
+                host: Some(host.to_owned()),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_ser_inline() {
+        let bucket = S3BucketSpec {
+            bucket_name: Some("test-bucket-name".to_owned()),
+            connection: Some(S3ConnectionDef::Inline(S3ConnectionSpec {
+                host: Some("host".to_owned()),
+                port: Some(8080),
+                endpoint_override: None,
+                endpoint_scheme_override: None,
+                region: None,
+                credentials: None,
+                access_style: Some(S3AccessStyle::VirtualHosted),
+                tls: None,
+                multipart_threshold: None,
+                multipart_part_size: None,
+                request_payer: None,
+                max_retries: None,
+                connection_type: None,
+            })),
+            connection_name: None,
+            base_path: None,
+        };
+
+        let mut buf = Vec::new();
+        yaml::serialize_to_explicit_document(&mut buf, &bucket).expect("serializable value");
+        let actual_yaml = str::from_utf8(&buf).expect("UTF-8 encoded document");
+
+        let expected_yaml = "---
+bucketName: test-bucket-name
+connection:
+  inline:
+    host: host
+    port: 8080
+    accessStyle: VirtualHosted
+";
+
+        assert_eq!(expected_yaml, actual_yaml);
+
+        // Omitting the region must not change the output, setting it adds the key
+        let mut bucket = bucket;
+        if let Some(S3ConnectionDef::Inline(connection)) = &mut bucket.connection {
+            connection.region = Some("eu-central-1".to_owned());
+        }
+
+        let mut buf = Vec::new();
+        yaml::serialize_to_explicit_document(&mut buf, &bucket).expect("serializable value");
+        let actual_yaml = str::from_utf8(&buf).expect("UTF-8 encoded document");
+        assert_eq!(
+            actual_yaml,
+            expected_yaml.replace(
+                "    accessStyle: VirtualHosted\n",
+                "    accessStyle: VirtualHosted\n    region: eu-central-1\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_connection_builder() {
+        let connection = S3ConnectionSpec::builder()
+            .host("host")
+            .port(8080)
+            .access_style(S3AccessStyle::VirtualHosted)
+            .build();
+
+        assert_eq!(
+            connection,
+            S3ConnectionSpec {
+                host: Some("host".to_owned()),
+                port: Some(8080),
+                access_style: Some(S3AccessStyle::VirtualHosted),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            serde_yaml::to_string(&connection).unwrap(),
+            "host: host\nport: 8080\naccessStyle: VirtualHosted\n"
+        );
+
+        let connection = S3ConnectionSpec::builder()
+            .host("minio")
+            .secret_class("minio-credentials")
+            .tls(serde_yaml::from_str("verification: {none: {}}").unwrap())
+            .region("eu-central-1")
+            .build();
+
+        assert_eq!(
+            connection.credentials,
+            Some(SecretClassVolume::new("minio-credentials".to_owned(), None))
+        );
+        assert!(connection.tls.is_some());
+        assert_eq!(connection.region.as_deref(), Some("eu-central-1"));
+        assert_eq!(connection.port, None);
+        assert_eq!(
+            S3ConnectionSpec::builder().build(),
+            S3ConnectionSpec::default()
+        );
+    }
+
+    #[rstest]
+    #[case::path(S3AccessStyle::Path, "host: minio\naccessStyle: Path\n")]
+    #[case::virtual_hosted(
+        S3AccessStyle::VirtualHosted,
+        "host: minio\naccessStyle: VirtualHosted\n"
+    )]
+    fn test_access_style_round_trip(#[case] access_style: S3AccessStyle, #[case] yaml: &str) {
+        let connection: S3ConnectionSpec = serde_yaml::from_str(yaml).expect("valid connection");
+        assert_eq!(connection.access_style, Some(access_style.clone()));
+        assert_eq!(serde_yaml::to_string(&connection).unwrap(), yaml);
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(connection)
+            .build();
+        assert_eq!(bucket.access_style(), access_style);
+    }
+
+    #[rstest]
+    #[case::unset("{host: minio}", S3AccessStyle::Path)]
+    #[case::aws_detected("{host: s3.amazonaws.com}", S3AccessStyle::VirtualHosted)]
+    #[case::connection_type("{host: minio, connectionType: Aws}", S3AccessStyle::VirtualHosted)]
+    fn test_bucket_access_style_default(#[case] connection: &str, #[case] expected: S3AccessStyle) {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(serde_yaml::from_str(connection).expect("valid connection"))
+            .build();
+        assert_eq!(bucket.access_style(), expected);
+    }
+
+    #[test]
+    fn test_access_style_unset() {
+        // The default of the enum itself is unchanged
+        assert_eq!(S3AccessStyle::default(), S3AccessStyle::VirtualHosted);
+
+        let connection: S3ConnectionSpec = serde_yaml::from_str("{host: minio}").unwrap();
+        assert_eq!(connection.access_style, None);
+        assert_eq!(connection.access_style_or_default(), S3AccessStyle::Path);
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(connection)
+            .build();
+        assert_eq!(bucket.access_style(), S3AccessStyle::Path);
+        assert_eq!(
+            InlinedS3BucketSpec::builder().build().access_style(),
+            S3AccessStyle::Path
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_concurrently_preserves_order() {
+        let delays = [60, 20, 40, 0];
+
+        let completed = Mutex::new(Vec::new());
+        let resolved = resolve_concurrently(&delays, |delay| {
+            let completed = &completed;
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(*delay)).await;
+                completed.lock().unwrap().push(*delay);
+                Ok(format!("bucket-{delay}"))
+            }
+        })
+        .await
+        .unwrap();
+
+        // The resolutions complete out of order, but are returned in the order of the input
+        assert_eq!(completed.into_inner().unwrap(), [0, 20, 40, 60]);
+        assert_eq!(
+            resolved,
+            ["bucket-60", "bucket-20", "bucket-40", "bucket-0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_concurrently_fails() {
+        let result = resolve_concurrently(&[false, true, false], |fail| async move {
+            match fail {
+                true => BucketNameRequiredSnafu.fail(),
+                false => Ok(()),
+            }
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::BucketNameRequired)));
+    }
+
+    /// Returns a client backed by a mock API server, which answers every request with the
+    /// requested [`S3Bucket`] (or [`S3Connection`]). The bucket name (or host) is set to the
+    /// namespace the object was requested in.
+    fn mock_client(default_namespace: &str) -> Client {
+        mock_client_with_buckets(default_namespace, Vec::new())
+    }
+
+    /// Like [`mock_client`], but answers list requests with the given `buckets`. Requests for
+    /// other objects (like ConfigMaps) are answered with 404 Not Found.
+    fn mock_client_with_buckets(default_namespace: &str, buckets: Vec<S3Bucket>) -> Client {
+        let service = tower::service_fn(move |request: hyper::Request<kube::client::Body>| {
+            let buckets = buckets.clone();
+            async move { Ok::<_, Infallible>(mock_response(&request, buckets)) }
+        });
+
+        Client::new(
+            kube::Client::new(service, default_namespace),
+            None,
+            default_namespace.to_owned(),
+        )
+    }
+
+    fn mock_response(
+        request: &hyper::Request<kube::client::Body>,
+        buckets: Vec<S3Bucket>,
+    ) -> hyper::Response<kube::client::Body> {
+        let mut segments = request
+            .uri()
+            .path()
+            .split('/')
+            .skip_while(|segment| *segment != "namespaces")
+            .skip(1)
+            .map(str::to_owned);
+        let namespace = segments
+            .next()
+            .expect("S3 objects must be requested in a namespace");
+        let plural = segments.next().expect("a resource type must be requested");
+
+        let Some(name) = segments.next() else {
+            return json_response(serde_json::json!({
+                "apiVersion": format!("{S3_API_GROUP}/{S3_API_VERSION}"),
+                "kind": "S3BucketList",
+                "metadata": {},
+                "items": buckets,
+            }));
+        };
+
+        let metadata = ObjectMeta {
+            name: Some(name.clone()),
+            namespace: Some(namespace.clone()),
+            uid: Some("6f0e2a1c-3b4d-4e5f-8a9b-0c1d2e3f4a5b".to_owned()),
+            ..ObjectMeta::default()
+        };
+        let object = match plural.as_str() {
+            "s3buckets" => serde_json::to_value(S3Bucket {
+                metadata,
+                spec: S3BucketSpec {
+                    bucket_name: Some(namespace),
+                    ..Default::default()
+                },
+            }),
+            "s3connections" => serde_json::to_value(S3Connection {
+                metadata,
+                spec: S3ConnectionSpec {
+                    host: Some(namespace),
+                    ..Default::default()
+                },
+            }),
+            _ => {
+                let mut response = json_response(serde_json::json!({
+                    "apiVersion": "v1",
+                    "kind": "Status",
+                    "metadata": {},
+                    "status": "Failure",
+                    "message": format!("{plural} {name:?} not found"),
+                    "reason": "NotFound",
+                    "code": 404,
+                }));
+                *response.status_mut() = hyper::StatusCode::NOT_FOUND;
+                return response;
+            }
+        };
+
+        json_response(object.expect("S3 objects must be serializable"))
+    }
+
+    fn json_response(value: serde_json::Value) -> hyper::Response<kube::client::Body> {
+        hyper::Response::new(kube::client::Body::from(value.to_string().into_bytes()))
+    }
+
+    #[tokio::test]
+    async fn test_get_in_namespace_or_default_explicit() {
+        let client = mock_client("default");
+
+        let bucket = S3BucketSpec::get_in_namespace_or_default("my-bucket", &client, Some("ns"))
+            .await
+            .unwrap();
+
+        assert_eq!(bucket.bucket_name.as_deref(), Some("ns"));
+    }
+
+    #[tokio::test]
+    async fn test_get_in_namespace_or_default_fallback() {
+        let client = mock_client("default");
+
+        let bucket = S3BucketSpec::get_in_namespace_or_default("my-bucket", &client, None)
+            .await
+            .unwrap();
+
+        assert_eq!(bucket.bucket_name.as_deref(), Some("default"));
+    }
+
+    #[tokio::test]
+    async fn test_get_resource() {
+        let client = mock_client("default");
+
+        let bucket = S3Bucket::get_resource("my-bucket", &client, "ns")
+            .await
+            .unwrap();
+        assert_eq!(bucket.name_any(), "my-bucket");
+        assert_eq!(bucket.namespace().as_deref(), Some("ns"));
+        assert!(bucket.uid().is_some());
+        assert_eq!(bucket.spec.bucket_name.as_deref(), Some("ns"));
+
+        let connection = S3Connection::get_resource("my-connection", &client, "ns")
+            .await
+            .unwrap();
+        assert_eq!(connection.name_any(), "my-connection");
+        assert_eq!(connection.namespace().as_deref(), Some("ns"));
+        assert!(connection.uid().is_some());
+        assert_eq!(connection.spec.host.as_deref(), Some("ns"));
+
+        // The spec-only getters return the spec of the very same objects
+        let connection_spec = S3ConnectionSpec::get("my-connection", &client, "ns")
+            .await
+            .unwrap();
+        assert_eq!(connection_spec, connection.spec);
+    }
+
+    #[tokio::test]
+    async fn test_get_in_namespace_or_default_no_namespace() {
+        let client = mock_client("");
+
+        let error = S3BucketSpec::get_in_namespace_or_default("my-bucket", &client, None)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, Error::NoNamespace { resource_name } if resource_name == "my-bucket")
+        );
+    }
+
+    fn bucket_with_connection(name: &str, connection: S3ConnectionDef) -> S3Bucket {
+        let mut bucket = S3Bucket::new(
+            name,
+            S3BucketSpec {
+                bucket_name: Some(name.to_owned()),
+                connection: Some(connection),
+                ..Default::default()
+            },
+        );
+        bucket.metadata.namespace = Some("ns".to_owned());
+        bucket
+    }
+
+    #[tokio::test]
+    async fn test_list_by_labels() {
+        let client = mock_client_with_buckets(
+            "default",
+            vec![
+                bucket_with_connection(
+                    "inline",
+                    S3ConnectionDef::Inline(S3ConnectionSpec::builder().host("minio").build()),
+                ),
+                bucket_with_connection(
+                    "referenced",
+                    S3ConnectionDef::Reference("my-connection".to_owned()),
+                ),
+            ],
+        );
+
+        let buckets = S3BucketSpec::list_by_labels(&LabelSelector::default(), &client, Some("ns"))
+            .await
+            .unwrap();
+
+        let hosts: Vec<_> = buckets
+            .iter()
+            .map(|(name, bucket)| {
+                let host = bucket.connection.as_ref().and_then(|c| c.host.as_deref());
+                (name.as_str(), host)
+            })
+            .collect();
+        assert_eq!(
+            hosts,
+            [("inline", Some("minio")), ("referenced", Some("ns"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_by_labels_empty() {
+        let client = mock_client("default");
+
+        let buckets = S3BucketSpec::list_by_labels(&LabelSelector::default(), &client, None)
+            .await
+            .unwrap();
+
+        assert!(buckets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_by_labels_fails() {
+        let client = mock_client_with_buckets(
+            "default",
+            vec![
+                bucket_with_connection(
+                    "inline",
+                    S3ConnectionDef::Inline(S3ConnectionSpec::builder().host("minio").build()),
+                ),
+                bucket_with_connection(
+                    "broken",
+                    S3ConnectionDef::ConfigMapReference("missing".to_owned()),
+                ),
+            ],
+        );
+
+        let error = S3BucketSpec::list_by_labels(&LabelSelector::default(), &client, Some("ns"))
+            .await
+            .unwrap_err();
+
+        match error {
+            Error::InlineS3Bucket {
+                source,
+                resource_name,
+            } => {
+                assert_eq!(resource_name, "broken");
+                assert!(matches!(
+                    *source,
+                    Error::MissingS3ConnectionConfigMap { .. }
+                ));
+            }
+            error => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn test_credentials_volumes_and_mounts() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                credentials: Some(SecretClassVolume::new("s3-credentials".to_owned(), None)),
+                ..Default::default()
+            })
+            .build();
+
+        let (volumes, mounts) = bucket.credentials_volumes_and_mounts().unwrap();
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].name, "s3-credentials-s3-credentials");
+        assert!(volumes[0].ephemeral.is_some());
+
+        assert_eq!(mounts.len(), 1);
+        assert_eq!(mounts[0].name, volumes[0].name);
+        assert_eq!(mounts[0].mount_path, "/stackable/secrets/s3-credentials");
+
+        assert_eq!(
+            bucket.credentials_mount_paths(),
+            Some((
+                "/stackable/secrets/s3-credentials/accessKey".to_owned(),
+                "/stackable/secrets/s3-credentials/secretKey".to_owned()
+            ))
+        );
+
+        let env_vars = bucket.credentials_env_vars();
+        let env_vars: Vec<_> = env_vars
+            .iter()
+            .map(|env_var| {
+                assert!(env_var.value_from.is_none());
+                (env_var.name.as_str(), env_var.value.as_deref())
+            })
+            .collect();
+        assert_eq!(
+            env_vars,
+            [
+                (
+                    "S3_ACCESS_KEY_FILE",
+                    Some("/stackable/secrets/s3-credentials/accessKey")
+                ),
+                (
+                    "S3_SECRET_KEY_FILE",
+                    Some("/stackable/secrets/s3-credentials/secretKey")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_credentials_volumes_and_mounts_without_credentials() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                ..Default::default()
+            })
+            .build();
+
+        let (volumes, mounts) = bucket.credentials_volumes_and_mounts().unwrap();
+        assert!(volumes.is_empty());
+        assert!(mounts.is_empty());
+        assert_eq!(bucket.credentials_mount_paths(), None);
+        assert!(bucket.credentials_env_vars().is_empty());
+    }
+
+    #[rstest]
+    #[case::no_base_path(None, "data/file.parquet", "data/file.parquet")]
+    #[case::empty_base_path(Some(""), "/data/file.parquet", "/data/file.parquet")]
+    #[case::plain(Some("tenants/a"), "file.parquet", "tenants/a/file.parquet")]
+    #[case::trailing_slash(Some("tenants/a/"), "file.parquet", "tenants/a/file.parquet")]
+    #[case::leading_slash(Some("tenants/a"), "/file.parquet", "tenants/a/file.parquet")]
+    #[case::both_slashes(Some("tenants/a//"), "//file.parquet", "tenants/a/file.parquet")]
+    #[case::absolute_base_path(Some("/tenants/a"), "file.parquet", "/tenants/a/file.parquet")]
+    #[case::only_slash(Some("/"), "file.parquet", "file.parquet")]
+    #[case::empty_key(Some("tenants/a"), "", "tenants/a/")]
+    fn test_full_path(#[case] base_path: Option<&str>, #[case] key: &str, #[case] expected: &str) {
+        let mut builder = InlinedS3BucketSpec::builder();
+        if let Some(base_path) = base_path {
+            builder.base_path(base_path);
+        }
+
+        assert_eq!(builder.build().full_path(key), expected);
+    }
+
+    #[test]
+    fn test_base_path_serde() {
+        let bucket: S3BucketSpec =
+            serde_yaml::from_str("bucketName: data\nbasePath: tenants/a\n").unwrap();
+        assert_eq!(bucket.base_path.as_deref(), Some("tenants/a"));
+        assert_eq!(
+            serde_yaml::to_string(&bucket).unwrap(),
+            "bucketName: data\nbasePath: tenants/a\n"
+        );
+    }
+
+    #[test]
+    fn test_ensure_connection_configured() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .build()
+            .ensure_connection_configured();
+        assert!(matches!(
+            bucket,
+            Err(Error::NoS3ConnectionConfigured { bucket_name }) if bucket_name == "data"
+        ));
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .build()
+            .ensure_connection_configured()
+            .expect("an empty bucket is allowed");
+        assert_eq!(bucket.bucket_name, None);
+        assert!(bucket.connection.is_none());
+        assert_eq!(bucket.connection_name, None);
+        assert_eq!(bucket.base_path, None);
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .connection(S3ConnectionSpec::builder().host("minio").build())
+            .build()
+            .ensure_connection_configured()
+            .expect("a bucket with a connection is allowed");
+        assert_eq!(bucket.bucket_name.as_deref(), Some("data"));
+    }
+
+    #[test]
+    fn test_gvk() {
+        let gvk = S3Bucket::gvk();
+        assert_eq!(gvk.group, S3Bucket::group(&()));
+        assert_eq!(gvk.version, S3Bucket::version(&()));
+        assert_eq!(gvk.kind, S3Bucket::kind(&()));
+        assert_eq!(
+            S3Bucket::api_version(&()),
+            format!("{S3_API_GROUP}/{S3_API_VERSION}")
+        );
+
+        let gvk = S3Connection::gvk();
+        assert_eq!(gvk.group, S3Connection::group(&()));
+        assert_eq!(gvk.version, S3Connection::version(&()));
+        assert_eq!(gvk.kind, S3Connection::kind(&()));
+        assert_eq!(
+            S3Connection::api_version(&()),
+            format!("{S3_API_GROUP}/{S3_API_VERSION}")
+        );
+    }
+
+    #[test]
+    fn test_region() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                region: Some("eu-central-1".to_owned()),
+                ..Default::default()
+            })
+            .build();
+        assert_eq!(bucket.region(), Some("eu-central-1"));
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec::default())
+            .build();
+        assert_eq!(bucket.region(), None);
+        assert_eq!(InlinedS3BucketSpec::builder().build().region(), None);
+    }
+
+    #[rstest]
+    #[case(
+        Some(RequestPayer::Requester),
+        "host: minio\nrequestPayer: Requester\n"
+    )]
+    #[case(
+        Some(RequestPayer::BucketOwner),
+        "host: minio\nrequestPayer: BucketOwner\n"
+    )]
+    #[case(None, "host: minio\n")]
+    fn test_request_payer_serialization(
+        #[case] request_payer: Option<RequestPayer>,
+        #[case] expected: &str,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            request_payer,
+            ..Default::default()
+        };
+
+        let serialized = serde_yaml::to_string(&connection).unwrap();
+        assert_eq!(serialized, expected);
+
+        let deserialized: S3ConnectionSpec = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, connection);
+    }
+
+    #[rstest]
+    #[case(Some(10), "host: minio\nmaxRetries: 10\n")]
+    #[case(None, "host: minio\n")]
+    fn test_max_retries_serialization(#[case] max_retries: Option<u32>, #[case] expected: &str) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            max_retries,
+            ..Default::default()
+        };
+
+        let serialized = serde_yaml::to_string(&connection).unwrap();
+        assert_eq!(serialized, expected);
+
+        let deserialized: S3ConnectionSpec = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, connection);
+    }
+
+    #[test]
+    fn test_dynamic_object_round_trip() {
+        let bucket: S3Bucket = serde_yaml::from_str(
+            "
+            apiVersion: s3.stackable.tech/v1alpha1
+            kind: S3Bucket
+            metadata:
+              name: my-bucket
+              namespace: default
+              labels:
+                app: minio
+              annotations:
+                description: test bucket
+            spec:
+              bucketName: my-bucket
+              connection:
+                inline:
+                  host: minio
+                  port: 9000
+                  accessStyle: Path
+            ",
+        )
+        .expect("valid S3Bucket");
+
+        let object = bucket
+            .to_dynamic_object()
+            .expect("convertible to DynamicObject");
+        assert_eq!(object.name_any(), "my-bucket");
+        assert_eq!(object.namespace().as_deref(), Some("default"));
+        assert_eq!(object.labels(), bucket.labels());
+        assert_eq!(object.data["spec"]["bucketName"], "my-bucket");
+
+        let round_tripped = S3Bucket::from_dynamic_object(object).expect("convertible to S3Bucket");
+        assert_eq!(round_tripped.metadata, bucket.metadata);
+        assert_eq!(round_tripped.spec, bucket.spec);
+    }
+
+    #[test]
+    fn test_dynamic_object_wrong_type() {
+        let connection = connection_with_host("minio", "minio.default.svc");
+        let object: DynamicObject =
+            serde_json::from_value(serde_json::to_value(connection).unwrap())
+                .expect("valid DynamicObject");
+
+        let err = S3Bucket::from_dynamic_object(object).expect_err("S3Connection is no S3Bucket");
+        assert!(matches!(
+            err,
+            Error::UnexpectedDynamicObjectType { kind, .. } if kind == "S3Connection"
+        ));
+    }
+
+    #[rstest]
+    #[case(Some("minio"), Some(9000), Some("minio:9000"))]
+    #[case(Some("192.168.0.1"), Some(9000), Some("192.168.0.1:9000"))]
+    #[case(Some("fe80::1"), Some(9000), Some("[fe80::1]:9000"))]
+    #[case(Some("fe80::1"), None, Some("[fe80::1]"))]
+    #[case(Some("minio"), None, Some("minio"))]
+    #[case(None, Some(9000), None)]
+    fn test_host_port(
+        #[case] host: Option<&str>,
+        #[case] port: Option<u16>,
+        #[case] expected: Option<&str>,
+    ) {
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("my-bucket")
+            .connection(S3ConnectionSpec {
+                host: host.map(ToOwned::to_owned),
+                port,
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(bucket.host_port().as_deref(), expected);
+    }
+
+    #[test]
+    fn test_crd_validation_rules() {
+        let expected_rule = serde_json::json!({
+            "rule": "!has(self.tls) || has(self.host) || has(self.endpointOverride)",
+            "message": "host or endpointOverride must be set if tls is set",
+        });
+
+        let crd = serde_json::to_value(S3Connection::crd()).unwrap();
+        let spec_schema =
+            &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        assert_eq!(
+            spec_schema["x-kubernetes-validations"],
+            serde_json::json!([expected_rule])
+        );
+        assert!(spec_schema["properties"]["host"].is_object());
+
+        let crd = serde_json::to_value(S3Bucket::crd()).unwrap();
+        let inline_schema = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]
+            ["spec"]["properties"]["connection"]["properties"]["inline"];
+        assert_eq!(
+            inline_schema["x-kubernetes-validations"],
+            serde_json::json!([expected_rule])
+        );
+    }
+
+    #[test]
+    fn test_collect_secret_classes() {
+        let defs: Vec<S3BucketDef> = serde_yaml::from_str(
+            "
+            - reference: bucket-a
+            - reference: bucket-b
+            - reference: bucket-a
+            - inline:
+                bucketName: inline
+                connection:
+                  reference: minio
+            - inline:
+                bucketName: inline
+                connection:
+                  reference: minio
+            ",
+        )
+        .expect("valid bucket definitions");
+        assert_eq!(unique_bucket_defs(&defs).len(), 3);
+
+        let connection = |credentials: &str, tls: Option<&str>| S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            credentials: Some(SecretClassVolume::new(credentials.to_owned(), None)),
+            tls: tls.map(|tls| serde_yaml::from_str(tls).expect("valid TLS settings")),
+            ..Default::default()
+        };
+        let buckets = [
+            InlinedS3BucketSpec::builder()
+                .connection(connection(
+                    "s3-credentials",
+                    Some("verification: {server: {caCert: {secretClass: tls}}}"),
+                ))
+                .build(),
+            InlinedS3BucketSpec::builder()
+                .connection(connection(
+                    "s3-credentials",
+                    Some("verification: {server: {caCert: {webPki: {}}}}"),
+                ))
+                .build(),
+            InlinedS3BucketSpec::builder()
+                .connection(connection(
+                    "other-credentials",
+                    Some("verification: {none: {}}"),
+                ))
+                .build(),
+            InlinedS3BucketSpec::builder().build(),
+        ];
+
+        assert_eq!(
+            secret_classes(&buckets),
+            BTreeSet::from([
+                "other-credentials".to_owned(),
+                "s3-credentials".to_owned(),
+                "tls".to_owned(),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_secret_classes_fetches_connections_once() {
+        let connection_requests = Arc::new(AtomicUsize::new(0));
+        let service = tower::service_fn({
+            let connection_requests = connection_requests.clone();
+            move |request: hyper::Request<kube::client::Body>| {
+                if request.uri().path().contains("/s3connections/") {
+                    connection_requests.fetch_add(1, Ordering::SeqCst);
+                }
+                async move { Ok::<_, Infallible>(mock_response(&request, Vec::new())) }
+            }
+        });
+        let client = Client::new(
+            kube::Client::new(service, "default"),
+            None,
+            "default".to_owned(),
+        );
+        let defs: Vec<S3BucketDef> = serde_yaml::from_str(
+            "
+            - inline:
+                bucketName: first
+                connection:
+                  reference: minio
+            - inline:
+                bucketName: second
+                connection:
+                  reference: minio
+            ",
+        )
+        .expect("valid bucket definitions");
+
+        let secret_classes = collect_secret_classes(&defs, &client, "ns").await.unwrap();
+
+        assert!(secret_classes.is_empty());
+        assert_eq!(connection_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[rstest]
+    #[case::valid("data", "data")]
+    #[case::valid_dots("data.example.com", "data.example.com")]
+    #[case::valid_max_len(&"a".repeat(63), &"a".repeat(63))]
+    #[case::trimmed("-data_", "data")]
+    #[case::special_characters("data/2024", "data-2024")]
+    #[case::special_characters_unicode("bücket:1", "b-cket-1")]
+    #[case::empty("", "")]
+    #[case::too_long(&"a".repeat(100), &format!("{}-0a0bb1d9", "a".repeat(54)))]
+    #[case::too_long_distinct(
+        &format!("{}b", "a".repeat(99)),
+        &format!("{}-070bad20", "a".repeat(54))
+    )]
+    #[case::too_long_trimmed(
+        &format!("{}/{}", "a".repeat(53), "b".repeat(20)),
+        &format!("{}-2f875755", "a".repeat(53))
+    )]
+    fn test_sanitize_label_value(#[case] value: &str, #[case] expected: &str) {
+        let sanitized = sanitize_label_value(value);
+        assert_eq!(sanitized, expected);
+        assert!(sanitized.len() <= LABEL_VALUE_MAX_LEN);
+
+        // Sanitizing must be idempotent
+        assert_eq!(sanitize_label_value(&sanitized), sanitized);
+    }
+
+    #[test]
+    fn test_fnv1a_32() {
+        assert_eq!(fnv1a_32(b""), 0x811c9dc5);
+        assert_eq!(fnv1a_32(b"a"), 0xe40c292c);
+    }
+
+    #[test]
+    fn test_owned_by_label_and_selector() {
+        let bucket = S3Bucket::new(&"my-bucket".repeat(10), S3BucketSpec::default());
+
+        let label = bucket
+            .owned_by_label()
+            .expect("sanitized label must be valid");
+        assert_eq!(label.key().to_string(), OWNED_BY_LABEL_KEY);
+        assert_eq!(label.value().len(), 63);
+
+        let selector = bucket.owned_by_selector();
+        assert_eq!(selector.match_expressions, None);
+        assert_eq!(
+            selector.match_labels,
+            Some(BTreeMap::from([(
+                OWNED_BY_LABEL_KEY.to_owned(),
+                label.value().to_string()
+            )]))
+        );
+    }
+
+    #[rstest]
+    #[case::no_requirements(RequirementSet::default(), None)]
+    #[case::bucket(RequirementSet { requires_bucket: true, ..Default::default() }, Some("bucket"))]
+    #[case::region(RequirementSet { requires_region: true, ..Default::default() }, Some("region"))]
+    #[case::tls(RequirementSet { requires_tls: true, ..Default::default() }, Some("tls"))]
+    #[case::first_unmet(
+        RequirementSet { requires_region: true, requires_bucket: true, requires_tls: true },
+        Some("bucket")
+    )]
+    fn test_validate_requirements(
+        #[case] requirements: RequirementSet,
+        #[case] unmet: Option<&str>,
+    ) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            ..Default::default()
+        };
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(connection.clone())
+            .build();
+
+        let result = bucket.validate_requirements(&requirements);
+        match unmet {
+            None => assert!(result.is_ok()),
+            Some("bucket") => assert!(matches!(result, Err(Error::BucketNameRequired))),
+            Some("region") => assert!(matches!(result, Err(Error::RegionRequired))),
+            Some("tls") => assert!(matches!(result, Err(Error::TlsRequired))),
+            Some(unmet) => panic!("unexpected requirement {unmet}"),
+        }
+
+        // A bucket fulfilling all requirements must always be valid
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .connection(S3ConnectionSpec {
+                region: Some("eu-central-1".to_owned()),
+                tls: Some(
+                    serde_yaml::from_str("verification: {none: {}}").expect("valid TLS settings"),
+                ),
+                ..connection
+            })
+            .build();
+        assert!(bucket.validate_requirements(&requirements).is_ok());
+    }
+
+    #[rstest]
+    #[case::inline_tls(
+        "inline: {bucketName: data, connection: {inline: {host: minio, port: 9000, tls: {verification: {none: {}}}}}}",
+        r#"bucket "data" on endpoint https://minio:9000 (tls)"#
+    )]
+    #[case::inline_no_tls(
+        "inline: {bucketName: data, connection: {inline: {host: minio}}}",
+        r#"bucket "data" on endpoint http://minio:80 (no tls)"#
+    )]
+    #[case::inline_tls_default_port(
+        "inline: {bucketName: data, connection: {inline: {host: minio, tls: {verification: {none: {}}}}}}",
+        r#"bucket "data" on endpoint https://minio:443 (tls)"#
+    )]
+    #[case::inline_without_connection(
+        "inline: {bucketName: data}",
+        r#"bucket "data" without connection"#
+    )]
+    #[case::inline_without_host(
+        "inline: {connection: {inline: {port: 9000}}}",
+        "unnamed bucket on endpoint <unknown> (no tls)"
+    )]
+    fn test_event_message(#[case] bucket_def: &str, #[case] expected: &str) {
+        let bucket_def: S3BucketDef =
+            serde_yaml::from_str(bucket_def).expect("valid bucket definition");
+        let S3BucketDef::Inline(S3BucketSpec {
+            bucket_name,
+            connection: connection_def,
+            ..
+        }) = bucket_def
+        else {
+            panic!("bucket definition must be inline");
+        };
+        let connection = connection_def.map(|connection_def| match connection_def {
+            S3ConnectionDef::Inline(connection) => connection,
+            S3ConnectionDef::Reference(_) | S3ConnectionDef::ConfigMapReference(_) => {
+                panic!("connection must be inline")
+            }
+        });
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name_opt(bucket_name)
+            .connection_opt(connection)
+            .build();
+        assert_eq!(bucket.event_message(), expected);
+    }
+
+    #[test]
+    fn test_event_message_referenced() {
+        // A referenced bucket resolves to the spec of the referenced S3Bucket, whose connection
+        // is resolved from the referenced S3Connection.
+        let connection = S3ConnectionSpec {
+            host: Some("minio.default.svc".to_owned()),
+            port: Some(9000),
+            credentials: Some(SecretClassVolume::new("s3-credentials".to_owned(), None)),
+            tls: Some(
+                serde_yaml::from_str("verification: {server: {caCert: {secretClass: tls}}}")
+                    .expect("valid TLS settings"),
+            ),
+            ..Default::default()
+        };
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .connection(connection)
+            .build();
+
+        let message = bucket.event_message();
+        assert_eq!(
+            message,
+            r#"bucket "data" on endpoint https://minio.default.svc:9000 (tls)"#
+        );
+        assert!(!message.contains("s3-credentials"));
+    }
+
+    /// Guards tests modifying the environment of the test process, which is shared by all tests.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_from_env() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        std::env::set_var("S3_FROM_ENV_HOST", "localhost");
+        std::env::set_var("S3_FROM_ENV_PORT", "9000");
+        std::env::set_var("S3_FROM_ENV_REGION", "eu-central-1");
+        std::env::set_var("S3_FROM_ENV_TLS", "true");
+
+        let connection = S3ConnectionSpec::from_env("S3_FROM_ENV").expect("valid environment");
+        assert_eq!(
+            connection,
+            S3ConnectionSpec {
+                host: Some("localhost".to_owned()),
+                port: Some(9000),
+                region: Some("eu-central-1".to_owned()),
+                tls: Some(
+                    serde_yaml::from_str("verification: {server: {caCert: {webPki: {}}}}").unwrap()
+                ),
+                ..Default::default()
+            }
+        );
+
+        std::env::set_var("S3_FROM_ENV_TLS", "false");
+        std::env::remove_var("S3_FROM_ENV_PORT");
+        std::env::remove_var("S3_FROM_ENV_REGION");
+
+        let connection = S3ConnectionSpec::from_env("S3_FROM_ENV").expect("valid environment");
+        assert_eq!(connection.endpoint().as_deref(), Some("http://localhost"));
+        assert_eq!(connection.region, None);
+
+        std::env::remove_var("S3_FROM_ENV_HOST");
+        std::env::remove_var("S3_FROM_ENV_TLS");
+    }
+
+    #[rstest]
+    #[case::missing_host(&[("PORT", "9000")], "missing environment variable \"DEV_HOST\"")]
+    #[case::invalid_port(
+        &[("HOST", "localhost"), ("PORT", "s3")],
+        "invalid value \"s3\" of environment variable \"DEV_PORT\""
+    )]
+    #[case::invalid_tls(
+        &[("HOST", "localhost"), ("TLS", "yes")],
+        "invalid value \"yes\" of environment variable \"DEV_TLS\""
+    )]
+    fn test_from_env_invalid(#[case] vars: &[(&str, &str)], #[case] expected: &str) {
+        let vars = vars
+            .iter()
+            .map(|(suffix, value)| (format!("DEV_{suffix}"), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+
+        let err = S3ConnectionSpec::from_env_vars("DEV", |name| vars.get(name).cloned())
+            .expect_err("invalid environment");
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_from_strict_yaml() {
+        let yaml = "host: minio\nport: 9000\ncredentials:\n  secretClass: minio-credentials\n";
+
+        let connection = S3ConnectionSpec::from_strict_yaml(yaml).expect("valid connection");
+        assert_eq!(connection, serde_yaml::from_str(yaml).unwrap());
+    }
+
+    #[test]
+    fn test_from_strict_yaml_unknown_field() {
+        let yaml = "hots: minio\nport: 9000\n";
+
+        // The lenient path silently drops the misspelled host
+        let connection: S3ConnectionSpec = serde_yaml::from_str(yaml).expect("lenient parsing");
+        assert_eq!(connection.host, None);
+
+        let err = S3ConnectionSpec::from_strict_yaml(yaml).expect_err("unknown field");
+        assert!(matches!(&err, Error::UnknownS3ConnectionField { field, .. } if field == "hots"));
+        assert!(err
+            .to_string()
+            .starts_with("unknown field \"hots\" in the S3 connection"));
+    }
+
+    #[test]
+    fn test_from_config_map_data() {
+        let data = BTreeMap::from([
+            ("host".to_string(), "minio".to_string()),
+            ("port".to_string(), "9000".to_string()),
+            ("secretClass".to_string(), "minio-credentials".to_string()),
+        ]);
+
+        let connection =
+            S3ConnectionSpec::from_config_map_data("s3", &data).expect("valid ConfigMap");
+        assert_eq!(
+            connection,
+            S3ConnectionSpec {
+                host: Some("minio".to_string()),
+                port: Some(9000),
+                credentials: Some(SecretClassVolume::new(
+                    "minio-credentials".to_string(),
+                    None
+                )),
+                ..S3ConnectionSpec::default()
+            }
+        );
+
+        let data = BTreeMap::from([("host".to_string(), "minio".to_string())]);
+        let connection =
+            S3ConnectionSpec::from_config_map_data("s3", &data).expect("valid ConfigMap");
+        assert_eq!(connection.endpoint().as_deref(), Some("http://minio"));
+        assert_eq!(connection.credentials, None);
+    }
+
+    #[rstest]
+    #[case::missing_host(
+        &[("port", "9000")],
+        "missing key \"host\" in the S3 connection ConfigMap \"s3\""
+    )]
+    #[case::invalid_port(
+        &[("host", "minio"), ("port", "s3")],
+        "invalid value \"s3\" of key \"port\" in the S3 connection ConfigMap \"s3\""
+    )]
+    #[case::port_out_of_range(
+        &[("host", "minio"), ("port", "65536")],
+        "invalid value \"65536\" of key \"port\" in the S3 connection ConfigMap \"s3\""
+    )]
+    fn test_from_config_map_data_invalid(#[case] data: &[(&str, &str)], #[case] expected: &str) {
+        let data = data
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+
+        let err =
+            S3ConnectionSpec::from_config_map_data("s3", &data).expect_err("invalid ConfigMap");
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::port("{port: 9000}", Some("port"))]
+    #[case::tls("{tls: {verification: {none: {}}}}", Some("tls"))]
+    #[case::port_and_tls("{port: 9000, tls: {verification: {none: {}}}}", Some("port"))]
+    #[case::all_none("{}", None)]
+    #[case::credentials_only("{credentials: {secretClass: s3-credentials}}", None)]
+    #[case::fully_specified("{host: minio, port: 9000, tls: {verification: {none: {}}}}", None)]
+    #[case::endpoint_override("{endpointOverride: 'https://s3:9000', port: 9000}", None)]
+    #[case::port_zero("{host: minio, port: 0}", Some("port"))]
+    #[case::port_zero_endpoint_override(
+        "{endpointOverride: 'https://s3:9000', port: 0}",
+        Some("port")
+    )]
+    #[case::port_min("{host: minio, port: 1}", None)]
+    #[case::port_max("{host: minio, port: 65535}", None)]
+    #[case::scheme_http("{host: minio, endpointSchemeOverride: http}", None)]
+    #[case::scheme_https("{host: minio, endpointSchemeOverride: https}", None)]
+    #[case::scheme_s3a("{host: minio, endpointSchemeOverride: s3a}", None)]
+    #[case::scheme_ftp(
+        "{host: minio, endpointSchemeOverride: ftp}",
+        Some("endpointSchemeOverride")
+    )]
+    #[case::scheme_uppercase(
+        "{host: minio, endpointSchemeOverride: HTTPS}",
+        Some("endpointSchemeOverride")
+    )]
+    #[case::scheme_empty(
+        "{host: minio, endpointSchemeOverride: ''}",
+        Some("endpointSchemeOverride")
+    )]
+    fn test_validate(#[case] connection: &str, #[case] invalid_field: Option<&str>) {
+        let connection: S3ConnectionSpec =
+            serde_yaml::from_str(connection).expect("valid S3 connection");
+
+        match (connection.validate(), invalid_field) {
+            (Ok(()), None) => {}
+            (Err(Error::InvalidS3Connection { field, .. }), Some(expected)) => {
+                assert_eq!(field, expected)
+            }
+            (result, expected) => panic!("unexpected result {result:?}, expected {expected:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_map_reference_serde() {
+        let def: S3ConnectionDef =
+            serde_yaml::from_str("configMapReference: s3").expect("valid connection definition");
+        assert_eq!(def, S3ConnectionDef::ConfigMapReference("s3".to_string()));
+    }
+
+    #[rstest]
+    #[case::override_match(
+        "{host: a, endpointOverride: 'https://s3:9000'}",
+        "{host: b, port: 1234, endpointOverride: 'https://s3:9000'}",
+        true
+    )]
+    #[case::override_mismatch(
+        "{host: a, endpointOverride: 'https://s3:9000'}",
+        "{host: a, endpointOverride: 'https://s3:9001'}",
+        false
+    )]
+    #[case::override_vs_composed(
+        "{endpointOverride: 'http://s3:9000'}",
+        "{host: s3, port: 9000}",
+        false
+    )]
+    #[case::composed_match(
+        "{host: s3, port: 9000}",
+        "{host: s3, port: 9000, maxRetries: 3}",
+        true
+    )]
+    #[case::composed_mismatch("{host: s3, port: 9000}", "{host: s3}", false)]
+    fn test_connection_equivalent(
+        #[case] connection: &str,
+        #[case] other: &str,
+        #[case] expected: bool,
+    ) {
+        let connection: S3ConnectionSpec = serde_yaml::from_str(connection).unwrap();
+        let other: S3ConnectionSpec = serde_yaml::from_str(other).unwrap();
+
+        assert_eq!(connection.connection_equivalent(&other), expected);
+        assert_eq!(other.connection_equivalent(&connection), expected);
+    }
+
+    #[rstest]
+    #[case::tls_with_port(Some("verification: {none: {}}"), Some(9000), "https://minio:9000")]
+    #[case::tls_without_port(Some("verification: {none: {}}"), None, "https://minio:443")]
+    #[case::no_tls_with_port(None, Some(9000), "http://minio:9000")]
+    #[case::no_tls_without_port(None, None, "http://minio:80")]
+    fn test_bucket_endpoint_scheme(
+        #[case] tls: Option<&str>,
+        #[case] port: Option<u16>,
+        #[case] expected: &str,
+    ) {
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                port,
+                tls: tls.map(|tls| serde_yaml::from_str(tls).expect("valid TLS settings")),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(bucket.endpoint().as_deref(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::disabled(None, false, S3TlsVerification::Disabled)]
+    #[case::insecure(Some("verification: {none: {}}"), true, S3TlsVerification::Insecure)]
+    #[case::system_trust_store(
+        Some("verification: {server: {caCert: {webPki: {}}}}"),
+        true,
+        S3TlsVerification::SystemTrustStore
+    )]
+    #[case::secret_class(
+        Some("verification: {server: {caCert: {secretClass: s3-ca}}}"),
+        true,
+        S3TlsVerification::SecretClass("s3-ca")
+    )]
+    fn test_tls_verification(
+        #[case] tls: Option<&str>,
+        #[case] expected_enabled: bool,
+        #[case] expected: S3TlsVerification,
+    ) {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                tls: tls.map(|tls| serde_yaml::from_str(tls).expect("valid TLS settings")),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(bucket.tls_enabled(), expected_enabled);
+        assert_eq!(bucket.tls_verification(), expected);
+    }
+
+    #[test]
+    fn test_tls_verification_without_connection() {
+        let bucket = InlinedS3BucketSpec::builder().build();
+
+        assert!(!bucket.tls_enabled());
+        assert_eq!(bucket.tls_verification(), S3TlsVerification::Disabled);
+    }
+
+    #[test]
+    fn test_endpoint_url() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(
+                S3ConnectionSpec::builder()
+                    .host("my-minio.default.svc")
+                    .port(9000)
+                    .build(),
+            )
+            .build();
+
+        let url = bucket.endpoint_url().unwrap().unwrap();
+        assert_eq!(url.scheme(), "http");
+        assert_eq!(url.host_str(), Some("my-minio.default.svc"));
+        assert_eq!(url.port(), Some(9000));
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec::builder().host("my minio").build())
+            .build();
+        assert!(matches!(
+            bucket.endpoint_url(),
+            Err(Error::InvalidEndpointUrl { endpoint, .. }) if endpoint == "http://my minio:80"
+        ));
+
+        assert_eq!(
+            InlinedS3BucketSpec::builder()
+                .build()
+                .endpoint_url()
+                .unwrap(),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::explicit_with_tls(Some(9000), true, 9000)]
+    #[case::explicit_without_tls(Some(9000), false, 9000)]
+    #[case::explicit_default_port_of_other_protocol(Some(80), true, 80)]
+    #[case::tls(None, true, 443)]
+    #[case::no_tls(None, false, 80)]
+    fn test_effective_port(#[case] port: Option<u16>, #[case] tls: bool, #[case] expected: u16) {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            port,
+            tls: tls.then(|| serde_yaml::from_str("verification: {none: {}}").unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(connection.effective_port(), expected);
+    }
+
+    #[test]
+    fn test_bucket_endpoint_override() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                endpoint_override: Some("https://s3.example.com".to_owned()),
+                ..Default::default()
+            })
+            .build();
+
+        // The port is not added to the endpoint override
+        assert_eq!(bucket.endpoint().as_deref(), Some("https://s3.example.com"));
+    }
+
+    #[test]
+    fn test_endpoint_override() {
+        let connection = S3ConnectionSpec {
+            host: Some("minio".to_owned()),
+            port: Some(9000),
+            endpoint_override: Some("https://s3.example.com".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            connection.endpoint().as_deref(),
+            Some("https://s3.example.com")
+        );
+    }
+
+    #[rstest]
+    #[case::http("{host: minio, port: 9000}", "http://minio:9000")]
+    #[case::https(
+        "{host: minio, port: 443, tls: {verification: {none: {}}}}",
+        "https://minio:443"
+    )]
+    #[case::no_port("{host: minio}", "http://minio")]
+    #[case::endpoint_override("{port: 80, endpointOverride: 'https://s3:9000'}", "https://s3:9000")]
+    #[case::scheme_https_without_tls(
+        "{host: minio, port: 9000, endpointSchemeOverride: https}",
+        "https://minio:9000"
+    )]
+    #[case::scheme_http_with_tls(
+        "{host: minio, port: 9000, endpointSchemeOverride: http, tls: {verification: {none: {}}}}",
+        "http://minio:9000"
+    )]
+    #[case::scheme_s3a("{host: minio, endpointSchemeOverride: s3a}", "s3a://minio")]
+    fn test_endpoint_strict(#[case] connection: &str, #[case] expected: &str) {
+        let connection: S3ConnectionSpec = serde_yaml::from_str(connection).unwrap();
+
+        assert_eq!(connection.endpoint_strict().unwrap(), expected);
+        assert_eq!(connection.endpoint().as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn test_endpoint_strict_errors() {
+        let bucket = InlinedS3BucketSpec::builder().bucket_name("data").build();
+        assert!(matches!(
+            bucket.endpoint_strict(),
+            Err(Error::MissingConnection)
+        ));
+
+        let connection: S3ConnectionSpec = serde_yaml::from_str("{port: 9000}").unwrap();
+        assert!(matches!(
+            connection.endpoint_strict(),
+            Err(Error::MissingHost)
+        ));
+
+        let connection: S3ConnectionSpec =
+            serde_yaml::from_str("{host: minio, port: 80, tls: {verification: {none: {}}}}")
+                .unwrap();
+        assert!(matches!(
+            connection.endpoint_strict(),
+            Err(Error::TlsPortMismatch { port: 80, .. })
+        ));
+        // The permissive variant still builds the (most likely broken) endpoint
+        assert_eq!(connection.endpoint().as_deref(), Some("https://minio:80"));
+
+        let connection: S3ConnectionSpec =
+            serde_yaml::from_str("{host: minio, port: 443}").unwrap();
+        let err = connection.endpoint_strict().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the S3 connection uses http on the https port 443"
+        );
+
+        let connection: S3ConnectionSpec =
+            serde_yaml::from_str("{host: minio, endpointSchemeOverride: ftp}").unwrap();
+        assert!(matches!(
+            connection.endpoint_strict(),
+            Err(Error::InvalidS3Connection {
+                field: "endpointSchemeOverride",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_configured_fields_minimal() {
+        let connection: S3ConnectionSpec = serde_yaml::from_str("{host: minio}").unwrap();
+        assert_eq!(connection.configured_fields(), BTreeSet::from(["host"]));
+
+        assert!(S3ConnectionSpec::default().configured_fields().is_empty());
+    }
+
+    #[test]
+    fn test_configured_fields_full() {
+        let connection: S3ConnectionSpec = serde_yaml::from_str(
+            "
+            host: minio
+            port: 9000
+            endpointOverride: https://s3.example.com
+            endpointSchemeOverride: https
+            accessStyle: Path
+            region: eu-central-1
+            credentials:
+              secretClass: s3-credentials
+            tls:
+              verification:
+                none: {}
+            multipartThreshold: 64Mi
+            multipartPartSize: 32Mi
+            requestPayer: Requester
+            maxRetries: 5
+            connectionType: S3Compatible
+            ",
+        )
+        .unwrap();
+
+        // The names must match the keys of the serialized connection
+        let serialized = serde_json::to_value(&connection).unwrap();
+        let keys = serialized
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(connection.configured_fields(), keys);
+        assert_eq!(connection.configured_fields().len(), 13);
+    }
+
+    #[test]
+    fn test_merge_cluster_default() {
+        let default: S3ConnectionSpec = serde_yaml::from_str(
+            "
+            host: minio
+            port: 9000
+            region: eu-central-1
+            credentials:
+              secretClass: s3-credentials
+            maxRetries: 5
+            ",
+        )
+        .unwrap();
+
+        let mut connection: S3ConnectionSpec = serde_yaml::from_str("{accessStyle: Path}").unwrap();
+        connection.merge(&default);
+
+        assert_eq!(connection.endpoint().as_deref(), Some("http://minio:9000"));
+        assert_eq!(connection.access_style, Some(S3AccessStyle::Path));
+        assert_eq!(connection.region.as_deref(), Some("eu-central-1"));
+        assert_eq!(connection.credentials, default.credentials);
+        assert_eq!(connection.max_retries, Some(5));
+        assert_eq!(connection.tls, None);
+
+        // Merging the same defaults again must not change anything
+        let merged = connection.clone();
+        connection.merge(&default);
+        assert_eq!(connection, merged);
+    }
+
+    #[test]
+    fn test_merge_cluster_default_explicit_override() {
+        let default: S3ConnectionSpec =
+            serde_yaml::from_str("{host: minio, port: 9000, region: eu-central-1, maxRetries: 5}")
+                .unwrap();
+
+        let mut connection: S3ConnectionSpec =
+            serde_yaml::from_str("{host: s3.example.com, region: us-east-1, maxRetries: 0}")
+                .unwrap();
+        connection.merge(&default);
+
+        // The default port doesn't apply to the explicit host
+        assert_eq!(
+            connection.endpoint().as_deref(),
+            Some("http://s3.example.com")
+        );
+        assert_eq!(connection.region.as_deref(), Some("us-east-1"));
+        assert_eq!(connection.max_retries, Some(0));
+
+        let mut connection: S3ConnectionSpec =
+            serde_yaml::from_str("{endpointOverride: 'https://s3:9000'}").unwrap();
+        connection.merge(&default);
+        assert_eq!(connection.host, None);
+        assert_eq!(connection.port, None);
+        assert_eq!(connection.endpoint().as_deref(), Some("https://s3:9000"));
+    }
+
+    #[test]
+    fn test_secret_csi_attributes() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                credentials: Some(
+                    serde_yaml::from_str("{secretClass: s3-credentials, scope: {pod: true}}")
+                        .expect("valid credentials"),
+                ),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(
+            bucket.secret_csi_attributes(),
+            Some(BTreeMap::from([
+                (
+                    "secrets.stackable.tech/class".to_owned(),
+                    "s3-credentials".to_owned()
+                ),
+                ("secrets.stackable.tech/scope".to_owned(), "pod".to_owned()),
+            ]))
+        );
+
+        let bucket = InlinedS3BucketSpec::builder()
+            .connection(S3ConnectionSpec {
+                host: Some("minio".to_owned()),
+                ..Default::default()
+            })
+            .build();
+        assert_eq!(bucket.secret_csi_attributes(), None);
+        assert_eq!(
+            InlinedS3BucketSpec::builder()
+                .build()
+                .secret_csi_attributes(),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::explicit(
+        "{bucketName: data, connection: {inline: {host: minio}}, connectionName: minio}",
+        Some("minio")
+    )]
+    #[case::explicit_overrides_reference(
+        "{bucketName: data, connection: {reference: minio}, connectionName: my-minio}",
+        Some("my-minio")
+    )]
+    #[case::reference("{bucketName: data, connection: {reference: minio}}", Some("minio"))]
+    #[case::inline("{bucketName: data, connection: {inline: {host: minio}}}", None)]
+    fn test_connection_name(#[case] bucket: &str, #[case] expected: Option<&str>) {
+        let bucket: S3BucketSpec = serde_yaml::from_str(bucket).expect("valid bucket");
+        assert_eq!(bucket.connection_name().as_deref(), expected);
+
+        let serialized = serde_yaml::to_string(&bucket).unwrap();
+        let deserialized: S3BucketSpec = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, bucket);
+    }
+
+    #[tokio::test]
+    #[ignore = "Tests depending on Kubernetes are not ran by default"]
+    async fn k8s_test_connection_name_inlined() {
+        let bucket: S3BucketSpec = serde_yaml::from_str(
+            "{bucketName: data, connection: {inline: {host: minio}}, connectionName: minio}",
+        )
+        .expect("valid bucket");
+
+        let client = crate::client::create_client(None)
+            .await
+            .expect("KUBECONFIG variable must be configured.");
+        let inlined = bucket.inlined(&client, "default").await.unwrap();
+        assert_eq!(inlined.connection_name.as_deref(), Some("minio"));
+    }
+
+    #[test]
+    fn test_builder_connection_name() {
+        let bucket = InlinedS3BucketSpec::builder()
+            .bucket_name("data")
+            .connection_name("minio")
+            .build();
+        assert_eq!(bucket.connection_name.as_deref(), Some("minio"));
+    }
+
+    #[test]
+    fn test_rbac_policy_rules() {
+        let rules = s3_rbac_policy_rules();
+        assert_eq!(rules.len(), 2);
+
+        let rule = &rules[0];
+        assert_eq!(
+            rule.api_groups,
+            Some(vec![S3Bucket::group(&()).to_string()])
+        );
+        assert_eq!(S3Bucket::group(&()), S3Connection::group(&()));
+
+        let resources = rule.resources.as_ref().expect("resources must be set");
+        assert!(resources.contains(&S3Bucket::plural(&()).to_string()));
+        assert!(resources.contains(&S3Connection::plural(&()).to_string()));
+
+        for verb in ["get", "list", "watch"] {
+            assert!(rule.verbs.contains(&verb.to_string()));
+        }
+
+        let rule = &rules[1];
+        assert_eq!(rule.api_groups, Some(vec!["".to_string()]));
+        assert_eq!(rule.resources, Some(vec!["configmaps".to_string()]));
+        assert_eq!(rule.verbs, vec!["get".to_string()]);
+    }
+
+    #[test]
+    fn test_get_by_label_single_match() {
+        let selector = LabelSelector::default();
+        let connections = vec![connection_with_host("minio", "minio.default.svc")];
+
+        let spec = S3ConnectionSpec::single_selector_match(connections, &selector, "default")
+            .expect("a single match must resolve");
+        assert_eq!(spec.host.as_deref(), Some("minio.default.svc"));
+    }
+
+    #[test]
+    fn test_get_by_label_no_match() {
+        let selector = LabelSelector::default();
+        let err = S3ConnectionSpec::single_selector_match(Vec::new(), &selector, "default")
+            .expect_err("no match must be an error");
+
+        assert!(matches!(err, Error::NoMatchingS3Connection { .. }));
+    }
+
+    #[test]
+    fn test_get_by_label_ambiguous_match() {
+        let selector = LabelSelector::default();
+        let connections = vec![
+            connection_with_host("minio", "minio.default.svc"),
+            connection_with_host("aws", "s3.amazonaws.com"),
+        ];
+
+        let err = S3ConnectionSpec::single_selector_match(connections, &selector, "default")
+            .expect_err("multiple matches must be an error");
+
+        match err {
+            Error::AmbiguousS3Connection { names, .. } => {
+                assert_eq!(names, vec!["minio".to_owned(), "aws".to_owned()])
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Strategies to determine the namespace, in which referenced S3 objects are looked up.
+//!
+//! Operators differ in where they expect the [`S3Bucket`](super::S3Bucket)s and
+//! [`S3Connection`](super::S3Connection)s referenced by their custom resources to live. Instead of
+//! computing the namespace at every call site, operators pass a [`NamespaceResolver`] to
+//! [`S3BucketDef::resolve_for`](super::S3BucketDef::resolve_for) (or
+//! [`S3ConnectionDef::resolve_for`](super::S3ConnectionDef::resolve_for)), which encapsulates
+//! their policy.
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use snafu::OptionExt;
+
+use crate::commons::s3::{Result, UnresolvedNamespaceSnafu};
+
+/// Determines the namespace, in which S3 objects referenced by an owning object are looked up.
+///
+/// Operators can implement this trait to plug in their own policy, e.g. reading the namespace
+/// from an annotation of the owner. The built-in resolvers are [`SameNamespace`] and
+/// [`FixedNamespace`].
+pub trait NamespaceResolver {
+    /// Returns the namespace to look up S3 objects referenced by the `owner` in, or [`None`] if
+    /// no namespace can be determined.
+    fn resolve(&self, owner: &ObjectMeta) -> Option<String>;
+}
+
+/// Looks up referenced S3 objects in the namespace of the owner.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SameNamespace;
+
+impl NamespaceResolver for SameNamespace {
+    fn resolve(&self, owner: &ObjectMeta) -> Option<String> {
+        owner.namespace.clone()
+    }
+}
+
+/// Looks up referenced S3 objects in a fixed namespace, independent of the owner.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixedNamespace(pub String);
+
+impl FixedNamespace {
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self(namespace.into())
+    }
+}
+
+impl NamespaceResolver for FixedNamespace {
+    fn resolve(&self, _owner: &ObjectMeta) -> Option<String> {
+        Some(self.0.clone())
+    }
+}
+
+/// Resolves the namespace for the `owner` using the `resolver` and returns an error if no
+/// namespace can be determined.
+pub(super) fn resolve_namespace(
+    resolver: &(impl NamespaceResolver + ?Sized),
+    owner: &ObjectMeta,
+) -> Result<String> {
+    resolver.resolve(owner).context(UnresolvedNamespaceSnafu {
+        owner: owner.name.clone().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commons::s3::Error;
+
+    fn owner(namespace: Option<&str>) -> ObjectMeta {
+        ObjectMeta {
+            name: Some("my-cluster".to_owned()),
+            namespace: namespace.map(ToOwned::to_owned),
+            ..ObjectMeta::default()
+        }
+    }
+
+    #[test]
+    fn test_same_namespace() {
+        assert_eq!(
+            resolve_namespace(&SameNamespace, &owner(Some("default"))).unwrap(),
+            "default"
+        );
+
+        let err = resolve_namespace(&SameNamespace, &owner(None)).unwrap_err();
+        assert!(matches!(err, Error::UnresolvedNamespace { owner } if owner == "my-cluster"));
+    }
+
+    #[test]
+    fn test_fixed_namespace() {
+        let resolver = FixedNamespace::new("s3-objects");
+
+        assert_eq!(
+            resolve_namespace(&resolver, &owner(Some("default"))).unwrap(),
+            "s3-objects"
+        );
+        assert_eq!(
+            resolve_namespace(&resolver, &owner(None)).unwrap(),
+            "s3-objects"
+        );
+    }
+
+    #[test]
+    fn test_custom_resolver() {
+        struct FromAnnotation;
+
+        impl NamespaceResolver for FromAnnotation {
+            fn resolve(&self, owner: &ObjectMeta) -> Option<String> {
+                owner
+                    .annotations
+                    .as_ref()?
+                    .get("s3.stackable.tech/namespace")
+                    .cloned()
+            }
+        }
+
+        let mut annotated = owner(Some("default"));
+        annotated.annotations = Some(
+            [(
+                "s3.stackable.tech/namespace".to_owned(),
+                "shared".to_owned(),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        // Trait objects are supported as well.
+        let resolver: &dyn NamespaceResolver = &FromAnnotation;
+        assert_eq!(resolve_namespace(resolver, &annotated).unwrap(), "shared");
+        assert!(resolve_namespace(resolver, &owner(Some("default"))).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Caching of [`S3Connection`](super::S3Connection) lookups within a reconcile.
+//!
+//! Buckets of a custom resource often reference the same `S3Connection`. Instead of fetching the
+//! connection once per bucket, operators can create a [`S3ConnectionResolver`] at the start of a
+//! reconcile and pass it to [`S3BucketDef::resolve_with`](super::S3BucketDef::resolve_with) (or
+//! [`S3ConnectionDef::resolve_with`](super::S3ConnectionDef::resolve_with)), which fetches every
+//! referenced connection only once.
+
+use std::{collections::HashMap, future::Future};
+
+use crate::{
+    client::Client,
+    commons::s3::{Result, S3ConnectionSpec},
+};
+
+/// Memoizes the `S3Connection`s fetched by [`S3ConnectionSpec::get`], keyed by their name and
+/// namespace.
+///
+/// The cache is never invalidated, so a resolver should only live as long as a single reconcile.
+/// Changes to the `S3Connection`s are picked up by creating a new resolver in the next reconcile.
+#[derive(Clone, Debug, Default)]
+pub struct S3ConnectionResolver {
+    cache: HashMap<(String, String), S3ConnectionSpec>,
+}
+
+impl S3ConnectionResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the spec of the `S3Connection` `resource_name` in `namespace`, which is only
+    /// fetched from the Kubernetes API if it wasn't fetched by this resolver before.
+    pub async fn get(
+        &mut self,
+        resource_name: &str,
+        client: &Client,
+        namespace: &str,
+    ) -> Result<S3ConnectionSpec> {
+        self.get_or_fetch(resource_name, namespace, || {
+            S3ConnectionSpec::get(resource_name, client, namespace)
+        })
+        .await
+    }
+
+    /// Returns the cached connection `resource_name` in `namespace` or calls `fetch` and caches
+    /// its result. Failed lookups are not cached.
+    async fn get_or_fetch<F, Fut>(
+        &mut self,
+        resource_name: &str,
+        namespace: &str,
+        fetch: F,
+    ) -> Result<S3ConnectionSpec>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<S3ConnectionSpec>>,
+    {
+        let key = (resource_name.to_owned(), namespace.to_owned());
+
+        if let Some(connection) = self.cache.get(&key) {
+            return Ok(connection.clone());
+        }
+
+        let connection = fetch().await?;
+        self.cache.insert(key, connection.clone());
+        Ok(connection)
+    }
+
+    /// Returns the number of cached connections.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns whether no connection is cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::commons::s3::{BucketNameRequiredSnafu, Error};
+
+    fn connection(host: &str) -> S3ConnectionSpec {
+        S3ConnectionSpec {
+            host: Some(host.to_owned()),
+            ..S3ConnectionSpec::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_second_lookup_is_cached() {
+        let calls = Cell::new(0);
+        let fetch = |host: &'static str| {
+            let calls = &calls;
+            move || async move {
+                calls.set(calls.get() + 1);
+                Ok(connection(host))
+            }
+        };
+        let mut resolver = S3ConnectionResolver::new();
+
+        let first = resolver
+            .get_or_fetch("minio", "default", fetch("minio"))
+            .await
+            .unwrap();
+        assert_eq!(first, connection("minio"));
+        assert_eq!(calls.get(), 1);
+
+        // The same name in the same namespace must not issue any additional call
+        let second = resolver
+            .get_or_fetch("minio", "default", fetch("other"))
+            .await
+            .unwrap();
+        assert_eq!(second, first);
+        assert_eq!(calls.get(), 1);
+
+        // Other names and namespaces are fetched separately
+        resolver
+            .get_or_fetch("minio", "other", fetch("minio"))
+            .await
+            .unwrap();
+        resolver
+            .get_or_fetch("aws", "default", fetch("aws"))
+            .await
+            .unwrap();
+        assert_eq!(calls.get(), 3);
+        assert_eq!(resolver.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_lookup_is_not_cached() {
+        let mut resolver = S3ConnectionResolver::new();
+
+        let err = resolver
+            .get_or_fetch("minio", "default", || async {
+                BucketNameRequiredSnafu.fail()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::BucketNameRequired));
+        assert!(resolver.is_empty());
+
+        let connection = resolver
+            .get_or_fetch("minio", "default", || async { Ok(connection("minio")) })
+            .await
+            .unwrap();
+        assert_eq!(connection.host.as_deref(), Some("minio"));
+    }
+}
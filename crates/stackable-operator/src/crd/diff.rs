@@ -0,0 +1,391 @@
+//! Detection of breaking changes between two revisions of a [`CustomResourceDefinition`].
+//!
+//! Changes to the schema of an already released CRD version must be backwards compatible.
+//! Otherwise, objects stored in Kubernetes (or manifests written by users) can become invalid.
+//! [`crd_breaking_changes`] compares the schemas of all versions of two CRDs and reports changes,
+//! which are known to be breaking.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    CustomResourceDefinition, JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool,
+};
+
+/// A breaking change detected by [`crd_breaking_changes`].
+///
+/// The `path` of a property uses a dotted notation, like `.spec.connection.host`. `[*]` refers to
+/// the items of an array and `.*` to the values of a map.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BreakingChange {
+    /// A version of the old CRD is not present in the new CRD anymore.
+    RemovedVersion { version: String },
+
+    /// A property present in the old schema was removed.
+    RemovedProperty { version: String, path: String },
+
+    /// A property was made required, or a required property was added.
+    NewRequiredProperty { version: String, path: String },
+
+    /// The type of a property was changed to a type accepting fewer values.
+    NarrowedType {
+        version: String,
+        path: String,
+        old: String,
+        new: String,
+    },
+
+    /// An allowed value of an enum was removed.
+    RemovedEnumValue {
+        version: String,
+        path: String,
+        value: String,
+    },
+}
+
+impl Display for BreakingChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BreakingChange::RemovedVersion { version } => {
+                write!(f, "version {version} was removed")
+            }
+            BreakingChange::RemovedProperty { version, path } => {
+                write!(f, "{version}: property {path} was removed")
+            }
+            BreakingChange::NewRequiredProperty { version, path } => {
+                write!(f, "{version}: property {path} is now required")
+            }
+            BreakingChange::NarrowedType {
+                version,
+                path,
+                old,
+                new,
+            } => write!(
+                f,
+                "{version}: type of property {path} was narrowed from {old} to {new}"
+            ),
+            BreakingChange::RemovedEnumValue {
+                version,
+                path,
+                value,
+            } => write!(f, "{version}: value {value} of property {path} was removed"),
+        }
+    }
+}
+
+/// Compares the schemas of all versions of the `old` and `new` CRD and returns the detected
+/// breaking changes. An empty list means that no breaking changes were detected.
+///
+/// The following changes are considered breaking:
+///
+/// - Removing a version.
+/// - Removing a property.
+/// - Making a property required (including adding new required properties).
+/// - Narrowing the type of a property, like changing `string` to `integer` or restricting an
+///   unconstrained property to a type. Widening `integer` to `number` is not breaking.
+/// - Removing values from an enum or restricting a property to an enum.
+pub fn crd_breaking_changes(
+    old: &CustomResourceDefinition,
+    new: &CustomResourceDefinition,
+) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    for old_version in &old.spec.versions {
+        let Some(new_version) = new
+            .spec
+            .versions
+            .iter()
+            .find(|v| v.name == old_version.name)
+        else {
+            changes.push(BreakingChange::RemovedVersion {
+                version: old_version.name.clone(),
+            });
+            continue;
+        };
+
+        let old_schema = old_version
+            .schema
+            .as_ref()
+            .and_then(|s| s.open_api_v3_schema.as_ref());
+        let new_schema = new_version
+            .schema
+            .as_ref()
+            .and_then(|s| s.open_api_v3_schema.as_ref());
+
+        if let (Some(old_schema), Some(new_schema)) = (old_schema, new_schema) {
+            compare_schemas(&old_version.name, "", old_schema, new_schema, &mut changes);
+        }
+    }
+
+    changes
+}
+
+fn compare_schemas(
+    version: &str,
+    path: &str,
+    old: &JSONSchemaProps,
+    new: &JSONSchemaProps,
+    changes: &mut Vec<BreakingChange>,
+) {
+    if let Some((old_type, new_type)) = narrowed_type(old, new) {
+        changes.push(BreakingChange::NarrowedType {
+            version: version.to_owned(),
+            path: display_path(path),
+            old: old_type,
+            new: new_type,
+        });
+    }
+
+    match (&old.enum_, &new.enum_) {
+        (Some(old_values), Some(new_values)) => {
+            for value in old_values.iter().filter(|v| !new_values.contains(*v)) {
+                changes.push(BreakingChange::RemovedEnumValue {
+                    version: version.to_owned(),
+                    path: display_path(path),
+                    value: value.0.to_string(),
+                });
+            }
+        }
+        (None, Some(_)) => changes.push(BreakingChange::NarrowedType {
+            version: version.to_owned(),
+            path: display_path(path),
+            old: type_name(old),
+            new: "enum".to_owned(),
+        }),
+        _ => {}
+    }
+
+    let empty = BTreeMap::new();
+    let old_properties = old.properties.as_ref().unwrap_or(&empty);
+    let new_properties = new.properties.as_ref().unwrap_or(&empty);
+
+    for (name, old_property) in old_properties {
+        let property_path = format!("{path}.{name}");
+
+        match new_properties.get(name) {
+            Some(new_property) => {
+                compare_schemas(version, &property_path, old_property, new_property, changes)
+            }
+            // Removed properties are kept if the new schema preserves unknown fields.
+            None if new.x_kubernetes_preserve_unknown_fields != Some(true) => {
+                changes.push(BreakingChange::RemovedProperty {
+                    version: version.to_owned(),
+                    path: property_path,
+                })
+            }
+            None => {}
+        }
+    }
+
+    let old_required = old.required.as_deref().unwrap_or_default();
+    for required in new.required.as_deref().unwrap_or_default() {
+        if !old_required.contains(required) {
+            changes.push(BreakingChange::NewRequiredProperty {
+                version: version.to_owned(),
+                path: format!("{path}.{required}"),
+            });
+        }
+    }
+
+    if let (
+        Some(JSONSchemaPropsOrArray::Schema(old_items)),
+        Some(JSONSchemaPropsOrArray::Schema(new_items)),
+    ) = (&old.items, &new.items)
+    {
+        compare_schemas(
+            version,
+            &format!("{path}[*]"),
+            old_items,
+            new_items,
+            changes,
+        );
+    }
+
+    if let (
+        Some(JSONSchemaPropsOrBool::Schema(old_values)),
+        Some(JSONSchemaPropsOrBool::Schema(new_values)),
+    ) = (&old.additional_properties, &new.additional_properties)
+    {
+        compare_schemas(
+            version,
+            &format!("{path}.*"),
+            old_values,
+            new_values,
+            changes,
+        );
+    }
+}
+
+/// Returns the old and new type name if the type of the property was narrowed.
+fn narrowed_type(old: &JSONSchemaProps, new: &JSONSchemaProps) -> Option<(String, String)> {
+    let old_int_or_string = old.x_kubernetes_int_or_string == Some(true);
+    let new_int_or_string = new.x_kubernetes_int_or_string == Some(true);
+
+    let narrowed = match (old.type_.as_deref(), new.type_.as_deref()) {
+        // An int-or-string is only narrowed if the new schema doesn't accept both anymore.
+        _ if old_int_or_string => !new_int_or_string,
+        // Every integer is a valid number as well.
+        (Some("integer"), Some("number")) => false,
+        (Some(old_type), Some(new_type)) => old_type != new_type,
+        // Restricting a property to a type narrows it, removing the type widens it.
+        (None, Some(_)) => !new_int_or_string && new.x_kubernetes_preserve_unknown_fields.is_none(),
+        (_, None) => false,
+    };
+
+    narrowed.then(|| (type_name(old), type_name(new)))
+}
+
+fn type_name(schema: &JSONSchemaProps) -> String {
+    if schema.x_kubernetes_int_or_string == Some(true) {
+        return "int-or-string".to_owned();
+    }
+
+    schema.type_.clone().unwrap_or_else(|| "any".to_owned())
+}
+
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        ".".to_owned()
+    } else {
+        path.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crd(versions: serde_json::Value) -> CustomResourceDefinition {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": { "name": "s3connections.s3.stackable.tech" },
+            "spec": {
+                "group": "s3.stackable.tech",
+                "names": { "kind": "S3Connection", "plural": "s3connections" },
+                "scope": "Namespaced",
+                "versions": versions,
+            }
+        }))
+        .expect("valid CRD")
+    }
+
+    fn version(name: &str, spec_schema: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "served": true,
+            "storage": true,
+            "schema": {
+                "openAPIV3Schema": {
+                    "type": "object",
+                    "required": ["spec"],
+                    "properties": { "spec": spec_schema },
+                }
+            }
+        })
+    }
+
+    fn old_crd() -> CustomResourceDefinition {
+        crd(serde_json::json!([version(
+            "v1alpha1",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "host": { "type": "string" },
+                    "port": { "type": "integer" },
+                    "accessStyle": { "type": "string", "enum": ["Path", "VirtualHosted"] },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                }
+            })
+        )]))
+    }
+
+    #[test]
+    fn non_breaking_changes() {
+        let new = crd(serde_json::json!([
+            version(
+                "v1alpha1",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "host": { "type": "string" },
+                        "port": { "type": "number" },
+                        "accessStyle": {
+                            "type": "string",
+                            "enum": ["Path", "VirtualHosted", "Auto"]
+                        },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "region": { "type": "string" },
+                    }
+                })
+            ),
+            version("v1", serde_json::json!({ "type": "object" }))
+        ]));
+
+        assert_eq!(crd_breaking_changes(&old_crd(), &new), Vec::new());
+        assert_eq!(crd_breaking_changes(&old_crd(), &old_crd()), Vec::new());
+    }
+
+    #[test]
+    fn breaking_changes() {
+        let new = crd(serde_json::json!([version(
+            "v1alpha1",
+            serde_json::json!({
+                "type": "object",
+                "required": ["region"],
+                "properties": {
+                    "port": { "type": "string" },
+                    "accessStyle": { "type": "string", "enum": ["Path"] },
+                    "tags": { "type": "array", "items": { "type": "integer" } },
+                    "region": { "type": "string" },
+                }
+            })
+        )]));
+
+        let version = "v1alpha1".to_owned();
+        assert_eq!(
+            crd_breaking_changes(&old_crd(), &new),
+            vec![
+                BreakingChange::RemovedEnumValue {
+                    version: version.clone(),
+                    path: ".spec.accessStyle".to_owned(),
+                    value: "\"VirtualHosted\"".to_owned(),
+                },
+                BreakingChange::RemovedProperty {
+                    version: version.clone(),
+                    path: ".spec.host".to_owned(),
+                },
+                BreakingChange::NarrowedType {
+                    version: version.clone(),
+                    path: ".spec.port".to_owned(),
+                    old: "integer".to_owned(),
+                    new: "string".to_owned(),
+                },
+                BreakingChange::NarrowedType {
+                    version: version.clone(),
+                    path: ".spec.tags[*]".to_owned(),
+                    old: "string".to_owned(),
+                    new: "integer".to_owned(),
+                },
+                BreakingChange::NewRequiredProperty {
+                    version,
+                    path: ".spec.region".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn removed_version() {
+        let new = crd(serde_json::json!([version(
+            "v1",
+            serde_json::json!({ "type": "object" })
+        )]));
+
+        assert_eq!(
+            crd_breaking_changes(&old_crd(), &new),
+            vec![BreakingChange::RemovedVersion {
+                version: "v1alpha1".to_owned()
+            }]
+        );
+    }
+}
@@ -11,6 +11,10 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
+mod diff;
+
+pub use diff::{crd_breaking_changes, BreakingChange};
+
 const DOCS_HOME_URL_PLACEHOLDER: &str = "DOCS_BASE_URL_PLACEHOLDER";
 const DOCS_HOME_BASE_URL: &str = "https://docs.stackable.tech/home";
 
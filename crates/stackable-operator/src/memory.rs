@@ -9,6 +9,7 @@
 //! For details on Kubernetes quantities see: <https://github.com/kubernetes/apimachinery/blob/master/pkg/api/resource/quantity.go>
 
 use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use schemars::JsonSchema;
 use serde::{de::Visitor, Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt, Snafu};
 
@@ -316,6 +317,20 @@ impl MemoryQuantity {
         }
     }
 
+    /// Returns the amount of bytes of this MemoryQuantity, rounded down to a
+    /// whole number. Useful for products, which expect sizes in plain bytes.
+    pub fn as_bytes(&self) -> u64 {
+        // The whole part is scaled using integer arithmetic, so that large quantities don't lose
+        // precision. Only the fractional part is scaled as a float.
+        let bytes_per_unit = 1024u64.pow(self.unit.exponential_scale_factor() as u32);
+        let whole = self.value.trunc() as u64;
+        let fraction = (self.value.fract() as f64 * bytes_per_unit as f64) as u64;
+
+        whole
+            .saturating_mul(bytes_per_unit)
+            .saturating_add(fraction)
+    }
+
     /// Scale up or down to the desired `BinaryMultiple`. Returns a new `Memory` and does
     /// not change itself.
     pub fn scale_to(&self, binary_multiple: BinaryMultiple) -> Self {
@@ -366,6 +381,16 @@ impl<'de> Deserialize<'de> for MemoryQuantity {
     }
 }
 
+impl JsonSchema for MemoryQuantity {
+    fn schema_name() -> String {
+        "MemoryQuantity".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
 impl FromStr for MemoryQuantity {
     type Err = Error;
 
@@ -721,4 +746,18 @@ mod test {
         let memory: Memory = serde_yaml::from_str(input).unwrap();
         assert_eq!(memory.memory, expected);
     }
+
+    #[rstest]
+    #[case("64Mi", 67_108_864)]
+    #[case("1Gi", 1_073_741_824)]
+    #[case("512Ki", 524_288)]
+    #[case("1.5Mi", 1_572_864)]
+    #[case("1.25Gi", 1_342_177_280)]
+    #[case("12Gi", 12_884_901_888)]
+    #[case("5Ti", 5_497_558_138_880)]
+    #[case("3Ei", 3_458_764_513_820_540_928)]
+    fn test_as_bytes(#[case] input: &str, #[case] expected: u64) {
+        let memory: MemoryQuantity = input.parse().unwrap();
+        assert_eq!(memory.as_bytes(), expected);
+    }
 }
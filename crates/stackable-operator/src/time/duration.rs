@@ -518,6 +518,42 @@ mod test {
         assert_eq!(s.dur.as_secs(), 1296122);
     }
 
+    #[rstest]
+    #[case("dur: 30", 30)]
+    #[case("dur: 0", 0)]
+    #[case("dur: 30s", 30)]
+    fn deserialize_integer_seconds(#[case] input: &str, #[case] expected_secs: u64) {
+        #[derive(Deserialize)]
+        struct S {
+            dur: Duration,
+        }
+
+        let s: S = serde_yaml::from_str(input).unwrap();
+        assert_eq!(s.dur.as_secs(), expected_secs);
+    }
+
+    #[test]
+    fn deserialize_negative_integer() {
+        #[derive(Debug, Deserialize)]
+        struct S {
+            #[allow(dead_code)]
+            dur: Duration,
+        }
+
+        assert!(serde_yaml::from_str::<S>("dur: -30").is_err());
+    }
+
+    #[test]
+    fn serialize_integer_input_as_string() {
+        #[derive(Deserialize, Serialize)]
+        struct S {
+            dur: Duration,
+        }
+
+        let s: S = serde_yaml::from_str("dur: 90").unwrap();
+        assert_eq!(serde_yaml::to_string(&s).unwrap(), "dur: 1m30s\n");
+    }
+
     #[test]
     fn serialize() {
         #[derive(Serialize)]
@@ -8,7 +8,8 @@ impl<'de> Visitor<'de> for DurationVisitor {
     type Value = Duration;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a string in any of the supported formats")
+        formatter
+            .write_str("a string in any of the supported formats or an integer amount of seconds")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -18,14 +19,37 @@ impl<'de> Visitor<'de> for DurationVisitor {
         let dur = v.parse::<Duration>().map_err(serde::de::Error::custom)?;
         Ok(dur)
     }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Duration::from_secs(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let secs = u64::try_from(v).map_err(|_| {
+            serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+        })?;
+        Ok(Duration::from_secs(secs))
+    }
 }
 
+/// A [`Duration`] can be deserialized from a string in any of the supported
+/// human-readable formats (like `30s` or `1h10m`) or from a bare (non-negative)
+/// integer, which is interpreted as an amount of seconds. This enables
+/// compatibility with tools which emit durations as integer seconds.
+///
+/// Serialization always produces the human-readable string format.
 impl<'de> Deserialize<'de> for Duration {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(DurationVisitor)
+        deserializer.deserialize_any(DurationVisitor)
     }
 }
 
@@ -1,3 +1,4 @@
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule;
 use schemars::schema::Schema;
 
 pub fn raw_object_schema(_: &mut schemars::gen::SchemaGenerator) -> Schema {
@@ -28,10 +29,39 @@ pub fn raw_object_list_schema(_: &mut schemars::gen::SchemaGenerator) -> Schema
     .expect("Failed to parse JSON of custom raw object list schema")
 }
 
+/// Adds the CEL `rules` to the `schema` using the `x-kubernetes-validations` extension, which
+/// enables Kubernetes to validate constraints spanning multiple fields (like "`host` or
+/// `endpointOverride` must be set if `tls` is set") when objects are created or updated. Rules
+/// already present in the `schema` are kept.
+///
+/// This is intended to post-process schemas generated by schemars, usually in functions used via
+/// `#[schemars(schema_with = "...")]`.
+pub fn add_validation_rules(
+    schema: Schema,
+    rules: impl IntoIterator<Item = ValidationRule>,
+) -> Schema {
+    let Schema::Object(mut schema_object) = schema else {
+        return schema;
+    };
+
+    let validations = schema_object
+        .extensions
+        .entry("x-kubernetes-validations".to_owned())
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+
+    if let serde_json::Value::Array(validations) = validations {
+        validations.extend(rules.into_iter().map(|rule| {
+            serde_json::to_value(rule).expect("Failed to serialize CEL validation rule")
+        }));
+    }
+
+    Schema::Object(schema_object)
+}
+
 #[cfg(test)]
 mod tests {
     use k8s_openapi::api::core::v1::PodTemplateSpec;
-    use schemars::JsonSchema;
+    use schemars::{gen::SchemaGenerator, JsonSchema};
     use serde::{Deserialize, Serialize};
 
     use super::*;
@@ -123,4 +153,26 @@ mod tests {
         // without setting the name of the container and wondering why it didn't work.
         serde_yaml::from_str::<Test>(input).expect("Failed to parse valid podOverride");
     }
+
+    #[test]
+    fn test_add_validation_rules() {
+        let schema = Test::json_schema(&mut SchemaGenerator::default());
+        let rule = ValidationRule {
+            rule: "has(self.podOverrides)".to_owned(),
+            message: Some("podOverrides must be set".to_owned()),
+            ..ValidationRule::default()
+        };
+
+        let schema = add_validation_rules(schema, [rule.clone()]);
+        let schema = add_validation_rules(schema, [rule]);
+
+        let schema = serde_json::to_value(schema).unwrap();
+        assert_eq!(
+            schema["x-kubernetes-validations"],
+            serde_json::json!([
+                {"rule": "has(self.podOverrides)", "message": "podOverrides must be set"},
+                {"rule": "has(self.podOverrides)", "message": "podOverrides must be set"},
+            ])
+        );
+    }
 }
@@ -2,6 +2,7 @@ pub mod bash;
 pub mod crds;
 pub mod logging;
 mod option;
+mod snake_case;
 mod url;
 
 #[deprecated(
@@ -15,7 +16,7 @@ pub use self::bash::COMMON_BASH_TRAP_FUNCTIONS;
 )]
 pub use self::logging::print_startup_string;
 
-pub use self::{option::OptionExt, url::UrlExt};
+pub use self::{option::OptionExt, snake_case::SnakeCase, url::UrlExt};
 
 /// Returns the fully qualified controller name, which should be used when a single controller needs to be referred to uniquely.
 ///
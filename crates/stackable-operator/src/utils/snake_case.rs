@@ -0,0 +1,260 @@
+//! This module contains the [`SnakeCase`] wrapper, which renders types using
+//! `snake_case` keys instead of the `camelCase` keys used by our CRDs.
+//!
+//! Our CRDs use `camelCase` keys via `#[serde(rename_all = "camelCase")]`,
+//! which is the convention for Kubernetes objects. Some (internal) tools
+//! consuming the same types outside of Kubernetes prefer `snake_case` keys.
+//! Wrapping a value in [`SnakeCase`] changes the serialized representation and
+//! the [`JsonSchema`] accordingly, without affecting the CRD representation of
+//! the wrapped type.
+
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{Schema, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+use serde::{ser::Error as _, Serialize, Serializer};
+
+/// Wraps a value to serialize it with `snake_case` instead of `camelCase`
+/// keys.
+///
+/// The keys of **all** JSON objects contained in the serialized value are
+/// converted, including keys of maps (like labels or annotations). The
+/// [`JsonSchema`] implementation converts the property names (and required
+/// properties) of the wrapped schema in the same way, so that the schema always
+/// matches the serialized representation. Subschemas are inlined, which is why
+/// the wrapper doesn't support recursive types.
+///
+/// ### Example
+///
+/// ```
+/// use serde::Serialize;
+/// use stackable_operator::utils::SnakeCase;
+///
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Foo {
+///     bucket_name: String,
+/// }
+///
+/// let foo = Foo {
+///     bucket_name: "my-bucket".to_owned(),
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&foo).unwrap(),
+///     r#"{"bucketName":"my-bucket"}"#
+/// );
+/// assert_eq!(
+///     serde_json::to_string(&SnakeCase(&foo)).unwrap(),
+///     r#"{"bucket_name":"my-bucket"}"#
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SnakeCase<T>(pub T);
+
+impl<T> Serialize for SnakeCase<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = serde_json::to_value(&self.0).map_err(S::Error::custom)?;
+        convert_value_keys(value).serialize(serializer)
+    }
+}
+
+impl<T> JsonSchema for SnakeCase<T>
+where
+    T: JsonSchema,
+{
+    fn schema_name() -> String {
+        format!("SnakeCase_{}", T::schema_name())
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        // Subschemas are inlined, because the definitions referenced by their
+        // name would otherwise still use camelCase property names.
+        let mut gen = SchemaSettings::default()
+            .with(|settings| settings.inline_subschemas = true)
+            .into_generator();
+
+        convert_schema_keys(gen.subschema_for::<T>())
+    }
+}
+
+/// Converts a `camelCase` (or `PascalCase`) input to `snake_case`. Inputs
+/// already using `snake_case` are returned unchanged.
+fn to_snake_case(input: &str) -> String {
+    let mut output = String::with_capacity(input.len() + 4);
+
+    for (index, chr) in input.char_indices() {
+        if chr.is_uppercase() {
+            if index != 0 {
+                output.push('_');
+            }
+            output.extend(chr.to_lowercase());
+        } else {
+            output.push(chr);
+        }
+    }
+
+    output
+}
+
+fn convert_value_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => serde_json::Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (to_snake_case(&key), convert_value_keys(value)))
+                .collect(),
+        ),
+        serde_json::Value::Array(array) => {
+            serde_json::Value::Array(array.into_iter().map(convert_value_keys).collect())
+        }
+        value => value,
+    }
+}
+
+fn convert_schema_keys(schema: Schema) -> Schema {
+    match schema {
+        Schema::Object(schema_object) => Schema::Object(convert_schema_object_keys(schema_object)),
+        schema => schema,
+    }
+}
+
+fn convert_schema_object_keys(mut schema_object: SchemaObject) -> SchemaObject {
+    if let Some(object) = schema_object.object.as_mut() {
+        object.properties = std::mem::take(&mut object.properties)
+            .into_iter()
+            .map(|(key, schema)| (to_snake_case(&key), convert_schema_keys(schema)))
+            .collect();
+        object.required = std::mem::take(&mut object.required)
+            .into_iter()
+            .map(|key| to_snake_case(&key))
+            .collect();
+        object.additional_properties = object
+            .additional_properties
+            .take()
+            .map(|schema| Box::new(convert_schema_keys(*schema)));
+    }
+
+    if let Some(array) = schema_object.array.as_mut() {
+        array.items = array.items.take().map(|items| match items {
+            SingleOrVec::Single(schema) => {
+                SingleOrVec::Single(Box::new(convert_schema_keys(*schema)))
+            }
+            SingleOrVec::Vec(schemas) => {
+                SingleOrVec::Vec(schemas.into_iter().map(convert_schema_keys).collect())
+            }
+        });
+    }
+
+    if let Some(subschemas) = schema_object.subschemas.as_mut() {
+        for schemas in [
+            &mut subschemas.all_of,
+            &mut subschemas.any_of,
+            &mut subschemas.one_of,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *schemas = std::mem::take(schemas)
+                .into_iter()
+                .map(convert_schema_keys)
+                .collect();
+        }
+    }
+
+    schema_object
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use schemars::schema_for;
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(JsonSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Outer {
+        bucket_name: String,
+        inner_values: Vec<Inner>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        optional_inner: Option<Inner>,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Inner {
+        secret_class: String,
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            bucket_name: "my-bucket".to_owned(),
+            inner_values: vec![Inner {
+                secret_class: "s3-credentials".to_owned(),
+            }],
+            optional_inner: None,
+        }
+    }
+
+    #[rstest]
+    #[case("bucketName", "bucket_name")]
+    #[case("BucketName", "bucket_name")]
+    #[case("bucket_name", "bucket_name")]
+    #[case("host", "host")]
+    fn snake_case(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_snake_case(input), expected);
+    }
+
+    #[test]
+    fn serialize_both_conventions() {
+        let outer = outer();
+
+        assert_eq!(
+            serde_json::to_string(&outer).unwrap(),
+            r#"{"bucketName":"my-bucket","innerValues":[{"secretClass":"s3-credentials"}]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&SnakeCase(&outer)).unwrap(),
+            r#"{"bucket_name":"my-bucket","inner_values":[{"secret_class":"s3-credentials"}]}"#
+        );
+    }
+
+    #[test]
+    fn schema_matches_serialization() {
+        let schema = serde_json::to_value(schema_for!(SnakeCase<Outer>)).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+
+        assert!(properties.contains_key("bucket_name"));
+        assert!(properties.contains_key("inner_values"));
+        assert!(properties.contains_key("optional_inner"));
+        assert!(!properties.contains_key("bucketName"));
+
+        let inner_properties = properties["inner_values"]["items"]["properties"]
+            .as_object()
+            .unwrap();
+        assert!(inner_properties.contains_key("secret_class"));
+
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&serde_json::json!("bucket_name")));
+
+        // The original schema must not be affected.
+        let schema = serde_json::to_value(schema_for!(Outer)).unwrap();
+        assert!(schema["properties"]
+            .as_object()
+            .unwrap()
+            .contains_key("bucketName"));
+    }
+}
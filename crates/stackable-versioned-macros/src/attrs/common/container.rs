@@ -1,11 +1,11 @@
-use std::{cmp::Ordering, ops::Deref};
+use std::ops::Deref;
 
 use darling::{
     util::{Flag, SpannedValue},
     Error, FromMeta, Result,
 };
-use itertools::Itertools;
 use k8s_version::Version;
+use syn::{Ident, Path, Type};
 
 /// This struct contains supported container attributes.
 ///
@@ -13,6 +13,23 @@ use k8s_version::Version;
 ///
 /// - `version`, which can occur one or more times. See [`VersionAttributes`].
 /// - `options`, which allow further customization of the generated code. See [`ContainerOptions`].
+/// - `validate`, which adds a validation function to every version. See [`ValidateAttributes`].
+/// - `derive_arbitrary`, which derives `arbitrary::Arbitrary` for every version.
+/// - `k8s`, which provides Kubernetes specific details. See [`KubernetesAttributes`].
+/// - `version_enum`, which generates an enum of all declared versions.
+/// - `conversions`, which controls the generated [`From`] implementations. See
+///   [`Conversions`].
+/// - `hooks`, which are called around the generated [`From`] and [`TryFrom`]
+///   implementations. See [`HooksAttributes`].
+/// - `builder`, which generates a builder for every version of a struct.
+/// - `downgrades`, which generates [`TryFrom`] implementations from newer to
+///   older (adjacent) versions.
+/// - `from_name`, which overrides the name of the source object in the
+///   generated [`From`] implementations.
+/// - `upgrade_to_latest`, which generates a function on the version enum
+///   deserializing a value of any version and upgrading it to the latest one.
+/// - `round_trip_tests`, which generates a test helper for every version of a
+///   struct asserting that conversions preserve the fields.
 #[derive(Debug, FromMeta)]
 #[darling(and_then = ContainerAttributes::validate)]
 pub(crate) struct ContainerAttributes {
@@ -21,14 +38,37 @@ pub(crate) struct ContainerAttributes {
 
     #[darling(default)]
     pub(crate) options: ContainerOptions,
+
+    pub(crate) validate: Option<ValidateAttributes>,
+
+    pub(crate) derive_arbitrary: Flag,
+
+    pub(crate) k8s: Option<KubernetesAttributes>,
+
+    pub(crate) version_enum: Flag,
+
+    #[darling(default)]
+    pub(crate) conversions: Conversions,
+
+    pub(crate) hooks: Option<HooksAttributes>,
+
+    pub(crate) builder: Flag,
+
+    pub(crate) downgrades: Flag,
+
+    pub(crate) from_name: Option<SpannedValue<String>>,
+
+    pub(crate) upgrade_to_latest: Flag,
+
+    pub(crate) round_trip_tests: Flag,
 }
 
 impl ContainerAttributes {
     fn validate(mut self) -> Result<Self> {
         // Most of the validation for individual version strings is done by the
         // k8s-version crate. That's why the code below only checks that at
-        // least one version is defined, they are defined in order (to ensure
-        // code consistency) and that all declared versions are unique.
+        // least one version is defined, that all declared versions are unique
+        // and that they are defined in order (to ensure code consistency).
 
         // If there are no versions defined, the derive macro errors out. There
         // should be at least one version if the derive macro is used.
@@ -39,18 +79,39 @@ impl ContainerAttributes {
             .with_span(&self.versions.span()));
         }
 
+        // Ensure every version is unique and isn't declared multiple times.
+        // Every duplicate is reported at the span of its repeated declaration.
+        let mut errors = Error::accumulator();
+
+        for (index, version) in self.versions.iter().enumerate() {
+            if self.versions[..index]
+                .iter()
+                .any(|other| *other.name == *version.name)
+            {
+                errors.push(
+                    Error::custom(format!(
+                        "attribute macro `#[versioned()]` contains duplicate version `{name}`",
+                        name = *version.name
+                    ))
+                    .with_span(&version.name.span()),
+                );
+            }
+        }
+
+        errors.finish()?;
+
         // NOTE (@Techassi): Do we even want to allow to opt-out of this?
 
         // Ensure that versions are defined in sorted (ascending) order to keep
-        // code consistent.
+        // code consistent. Otherwise, the From implementations (which follow
+        // the order of declaration) would convert into older versions.
         if !self.options.allow_unsorted.is_present() {
             let original = self.versions.deref().clone();
-            self.versions
-                .sort_by(|lhs, rhs| lhs.name.partial_cmp(&rhs.name).unwrap_or(Ordering::Equal));
+            self.versions.sort_by_key(|version| *version.name);
 
             for (index, version) in original.iter().enumerate() {
-                if version.name
-                    == self
+                if *version.name
+                    == *self
                         .versions
                         .get(index)
                         .expect("internal error: version at that index must exist")
@@ -61,28 +122,71 @@ impl ContainerAttributes {
 
                 return Err(Error::custom(format!(
                     "versions in `#[versioned()]` must be defined in ascending order (version `{name}` is misplaced)",
-                    name = version.name
-                )));
+                    name = *version.name
+                ))
+                .with_span(&version.name.span()));
             }
         }
 
-        // TODO (@Techassi): Add validation for skip(from) for last version,
-        // which will skip nothing, because nothing is generated in the first
-        // place.
-
-        // Ensure every version is unique and isn't declared multiple times.
-        let duplicates = self
+        // Skipping the From implementation of the last version skips nothing,
+        // because there is no next version to convert into in the first place.
+        let last_version = self
             .versions
-            .iter()
-            .duplicates_by(|e| e.name)
-            .map(|e| e.name)
-            .join(", ");
-
-        if !duplicates.is_empty() {
-            return Err(Error::custom(format!(
-                "attribute macro `#[versioned()]` contains duplicate versions: {duplicates}",
-            ))
-            .with_span(&self.versions.span()));
+            .last()
+            .expect("internal error: there must be at least one version");
+
+        if let Some(skip) = &last_version.skip {
+            if skip.from.is_present() {
+                return Err(Error::custom(format!(
+                    "version `{name}` is the last version and thus has no `From` implementation to skip",
+                    name = *last_version.name
+                ))
+                .with_span(&skip.from.span()));
+            }
+        }
+
+        let skip_from_all = self
+            .options
+            .skip
+            .as_ref()
+            .is_some_and(|skip| skip.from.is_present());
+
+        // The enum holding any version upgrades values by chaining the From
+        // implementations, which follow the order of declaration. The chain
+        // must end at the latest version (which is also used by the `Latest`
+        // alias), which is why it must be declared last.
+        if self.version_enum.is_present() && !skip_from_all {
+            let latest_version = self
+                .versions
+                .iter()
+                .max_by_key(|version| *version.name)
+                .expect("internal error: there must be at least one version");
+
+            if *latest_version.name != *last_version.name {
+                return Err(Error::custom(format!(
+                    "the latest version `{name}` must be declared last when using `version_enum`, because values are upgraded in the order of declaration",
+                    name = *latest_version.name
+                ))
+                .with_span(&latest_version.name.span()));
+            }
+        }
+
+        // Upgrading to the latest version is provided by the version enum and
+        // chains the generated From implementations.
+        if self.upgrade_to_latest.is_present() {
+            if !self.version_enum.is_present() {
+                return Err(
+                    Error::custom("`upgrade_to_latest` requires the `version_enum` flag")
+                        .with_span(&self.upgrade_to_latest.span()),
+                );
+            }
+
+            if skip_from_all {
+                return Err(Error::custom(
+                    "`upgrade_to_latest` requires `From` implementations, which are skipped for all versions",
+                )
+                .with_span(&self.upgrade_to_latest.span()));
+            }
         }
 
         Ok(self)
@@ -100,7 +204,7 @@ impl ContainerAttributes {
 #[derive(Clone, Debug, FromMeta)]
 pub(crate) struct VersionAttributes {
     pub(crate) deprecated: Flag,
-    pub(crate) name: Version,
+    pub(crate) name: SpannedValue<Version>,
     pub(crate) skip: Option<SkipOptions>,
     pub(crate) doc: Option<String>,
 }
@@ -118,6 +222,32 @@ pub(crate) struct ContainerOptions {
     pub(crate) skip: Option<SkipOptions>,
 }
 
+/// This enum contains the supported modes of generating [`From`]
+/// implementations between versions (in the order of declaration):
+///
+/// - `adjacent` (default) generates implementations between adjacent versions
+///   only. Conversions across multiple versions require multiple `into` calls.
+/// - `to_latest` additionally generates implementations from every version to
+///   the latest version.
+/// - `all_pairs` generates implementations from every version to every later
+///   version.
+///
+/// Implementations spanning multiple versions chain the implementations of the
+/// adjacent versions. They are thus not generated if the generation of `From`
+/// implementations is skipped for any version in between.
+#[derive(Clone, Copy, Debug, Default, Eq, FromMeta, PartialEq)]
+pub(crate) enum Conversions {
+    #[default]
+    #[darling(rename = "adjacent")]
+    Adjacent,
+
+    #[darling(rename = "to_latest")]
+    ToLatest,
+
+    #[darling(rename = "all_pairs")]
+    AllPairs,
+}
+
 /// This struct contains supported skip options.
 ///
 /// Supported options are:
@@ -127,3 +257,70 @@ pub(crate) struct ContainerOptions {
 pub(crate) struct SkipOptions {
     pub(crate) from: Flag,
 }
+
+/// This struct contains the supported validate options.
+///
+/// Supported options are:
+///
+/// - `with`, the path to the function which validates the container. The
+///   function is called with a reference to the container of each version and
+///   must therefore accept all versions (usually by being generic).
+/// - `error`, the error type returned by the validation function.
+#[derive(Clone, Debug, FromMeta)]
+pub(crate) struct ValidateAttributes {
+    pub(crate) with: Path,
+    pub(crate) error: Type,
+}
+
+/// This struct contains the supported conversion hook options.
+///
+/// Supported options are:
+///
+/// - `pre_convert`, the path to the function which is called with a reference
+///   to the source object before every generated conversion.
+/// - `post_convert`, the path to the function which is called with a reference
+///   to the produced object after every generated conversion.
+///
+/// Both functions are called for the containers of (almost) all versions and
+/// must therefore accept all versions (usually by being generic).
+#[derive(Clone, Debug, FromMeta)]
+pub(crate) struct HooksAttributes {
+    pub(crate) pre_convert: Option<Path>,
+    pub(crate) post_convert: Option<Path>,
+}
+
+/// This struct contains the supported Kubernetes options.
+///
+/// Supported options are:
+///
+/// - `group`, the API group of the container, like `s3.stackable.tech`.
+/// - `try_from_dynamic_object`, which generates a `TryFrom<&DynamicObject>`
+///   implementation for every version.
+/// - `spec_json_schema`, which generates a `spec_json_schema` function for
+///   every version.
+/// - `kind`, the kind of the custom resource derived from the container via
+///   `#[derive(CustomResource)]`. Required by `crd_documents`.
+/// - `crd_documents`, which generates a function returning the standalone CRD
+///   of every version.
+#[derive(Clone, Debug, FromMeta)]
+#[darling(and_then = KubernetesAttributes::validate)]
+pub(crate) struct KubernetesAttributes {
+    pub(crate) group: String,
+    pub(crate) try_from_dynamic_object: Flag,
+    pub(crate) spec_json_schema: Flag,
+    pub(crate) kind: Option<Ident>,
+    pub(crate) crd_documents: Flag,
+}
+
+impl KubernetesAttributes {
+    fn validate(self) -> Result<Self> {
+        if self.crd_documents.is_present() && self.kind.is_none() {
+            return Err(Error::custom(
+                "`crd_documents` requires the `kind` of the custom resource",
+            )
+            .with_span(&self.crd_documents.span()));
+        }
+
+        Ok(self)
+    }
+}
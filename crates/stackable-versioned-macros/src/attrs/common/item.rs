@@ -46,7 +46,7 @@ where
             if !container_attrs
                 .versions
                 .iter()
-                .any(|v| v.name == *added.since)
+                .any(|v| *v.name == *added.since)
             {
                 errors.push(Error::custom(
                    "variant action `added` uses version which was not declared via #[versioned(version)]")
@@ -59,7 +59,7 @@ where
             if !container_attrs
                 .versions
                 .iter()
-                .any(|v| v.name == *rename.since)
+                .any(|v| *v.name == *rename.since)
             {
                 errors.push(
                    Error::custom("variant action `renamed` uses version which was not declared via #[versioned(version)]")
@@ -72,7 +72,7 @@ where
             if !container_attrs
                 .versions
                 .iter()
-                .any(|v| v.name == *deprecated.since)
+                .any(|v| *v.name == *deprecated.since)
             {
                 errors.push(Error::custom(
                    "variant action `deprecated` uses version which was not declared via #[versioned(version)]")
@@ -309,7 +309,7 @@ impl ItemAttributes {
 ///
 /// Example usage:
 /// - `added(since = "...")`
-/// - `added(since = "...", default_fn = "custom_fn")`
+/// - `added(since = "...", default = "custom_fn")`
 #[derive(Clone, Debug, FromMeta)]
 pub(crate) struct AddedAttributes {
     pub(crate) since: SpannedValue<Version>,
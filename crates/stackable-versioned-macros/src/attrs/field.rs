@@ -1,5 +1,9 @@
-use darling::{Error, FromField};
-use syn::{Attribute, Ident};
+use darling::{
+    util::{Flag, SpannedValue},
+    Error, FromField, FromMeta,
+};
+use k8s_version::Version;
+use syn::{Attribute, Ident, Path, Type};
 
 use crate::attrs::common::{ItemAttributes, ItemType};
 
@@ -26,6 +30,17 @@ pub(crate) struct FieldAttributes {
     #[darling(flatten)]
     pub(crate) common: ItemAttributes,
 
+    /// Marks the field as a passthrough map, which preserves unknown fields.
+    /// This is only a lint: Passthrough fields cannot be deprecated or changed,
+    /// because that would (eventually) drop the preserved data. No code is
+    /// generated, the schema extension must still be set on the field.
+    pub(crate) passthrough: Flag,
+
+    /// This parses the `changed` attribute on fields. It can be present 0..n
+    /// times, once for every version in which the type of the field changed.
+    #[darling(multiple, rename = "changed")]
+    pub(crate) changes: Vec<ChangedAttributes>,
+
     // The ident (automatically extracted by darling) cannot be moved into the
     // shared item attributes because for struct fields, the type is
     // `Option<Ident>`, while for enum variants, the type is `Ident`.
@@ -51,7 +66,84 @@ impl FieldAttributes {
             .as_ref()
             .expect("internal error: field must have an ident");
         self.common.validate(ident, &ItemType::Field, &self.attrs)?;
+        self.validate_passthrough(ident)?;
+        self.validate_changes(ident)?;
 
         Ok(self)
     }
+
+    /// This associated function is called by the top-level validation function
+    /// and validates that passthrough fields are never dropped.
+    ///
+    /// Deprecating a field is the first step in removing it. Dropping a
+    /// passthrough map would silently discard the preserved unknown fields,
+    /// which is why passthrough fields cannot be marked as `deprecated`.
+    /// Removing such a field requires an explicit `removed` action, which is
+    /// not supported yet.
+    ///
+    /// Passthrough fields are copied verbatim, which is why their type cannot
+    /// be marked as `changed` either.
+    fn validate_passthrough(&self, ident: &Ident) -> Result<(), Error> {
+        if self.passthrough.is_present() && self.common.deprecated.is_some() {
+            return Err(Error::custom(
+                "field marked as `passthrough` cannot be marked as `deprecated`, because this would drop the preserved unknown fields",
+            )
+            .with_span(ident));
+        }
+
+        if self.passthrough.is_present() && !self.changes.is_empty() {
+            return Err(Error::custom(
+                "field marked as `passthrough` cannot be marked as `changed`, because passthrough fields are copied verbatim",
+            )
+            .with_span(ident));
+        }
+
+        Ok(())
+    }
+
+    /// This associated function is called by the top-level validation function
+    /// and validates that the type of a field changes at most once per version
+    /// and only after the field was added.
+    fn validate_changes(&self, ident: &Ident) -> Result<(), Error> {
+        let added_version = self.common.added.as_ref().map(|a| *a.since);
+
+        for (index, change) in self.changes.iter().enumerate() {
+            if added_version.is_some_and(|a| a >= *change.since) {
+                return Err(Error::custom(
+                    "field cannot be marked as `changed` in or before the version it was `added` in",
+                )
+                .with_span(&change.since.span()));
+            }
+
+            if self.changes[..index]
+                .iter()
+                .any(|other| *other.since == *change.since)
+            {
+                return Err(Error::custom(format!(
+                    "field was marked as `changed` multiple times in version `{version}`",
+                    version = *change.since
+                ))
+                .with_span(ident));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// For the changed() action
+///
+/// Example usage:
+/// - `changed(since = "...", from_type = "...", with = "...")`
+/// - `changed(since = "...", from_type = "...", with = "...", downgrade_with = "...")`
+/// - `changed(since = "...", from_type = "...")`
+///
+/// The conversion functions can be omitted if the field only becomes optional
+/// (`T` to `Option<T>`) or required (`Option<T>` to `T`).
+#[derive(Clone, Debug, FromMeta)]
+pub(crate) struct ChangedAttributes {
+    pub(crate) since: SpannedValue<Version>,
+    pub(crate) from_type: Type,
+    pub(crate) with: Option<Path>,
+    pub(crate) downgrade_with: Option<Path>,
 }
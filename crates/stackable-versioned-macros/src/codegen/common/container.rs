@@ -1,9 +1,16 @@
 use std::ops::Deref;
 
+use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
-use syn::{Attribute, Ident, Visibility};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Attribute, Generics, Ident, Visibility};
 
-use crate::{attrs::common::ContainerAttributes, codegen::common::ContainerVersion};
+use crate::{
+    attrs::common::{
+        ContainerAttributes, Conversions, HooksAttributes, KubernetesAttributes, ValidateAttributes,
+    },
+    codegen::common::ContainerVersion,
+};
 
 /// This trait helps to unify versioned containers, like structs and enums.
 ///
@@ -45,6 +52,7 @@ pub(crate) struct ContainerInput {
     pub(crate) original_attributes: Vec<Attribute>,
     pub(crate) visibility: Visibility,
     pub(crate) ident: Ident,
+    pub(crate) generics: Generics,
 }
 
 /// Stores individual versions of a single container.
@@ -65,6 +73,10 @@ pub(crate) struct VersionedContainer<I> {
     /// The ident, or name, of the versioned container.
     pub(crate) ident: Ident,
 
+    /// The generic parameters (including the where clause) of the versioned
+    /// container, which are forwarded onto the container of every version.
+    pub(crate) generics: Generics,
+
     /// The visibility of the versioned container. Used to forward the
     /// visibility during code generation.
     pub(crate) visibility: Visibility,
@@ -78,4 +90,785 @@ pub(crate) struct VersionedContainer<I> {
     /// Whether the [`From`] implementation generation should be skipped for all
     /// versions of this container.
     pub(crate) skip_from: bool,
+
+    /// The validation function (and its error type), which is called by the
+    /// generated `validate` function of every version.
+    pub(crate) validate: Option<ValidateAttributes>,
+
+    /// Whether `arbitrary::Arbitrary` should be derived for all versions of
+    /// this container.
+    pub(crate) derive_arbitrary: bool,
+
+    /// Kubernetes specific details, like the API group of this container.
+    pub(crate) k8s: Option<KubernetesAttributes>,
+
+    /// Whether an enum of all declared versions should be generated.
+    pub(crate) version_enum: bool,
+
+    /// Which [`From`] implementations should be generated in addition to the
+    /// ones between adjacent versions.
+    pub(crate) conversions: Conversions,
+
+    /// The functions called before and after every generated (adjacent)
+    /// [`From`] implementation.
+    pub(crate) hooks: Option<HooksAttributes>,
+
+    /// Whether a builder should be generated for every version. This is only
+    /// supported for structs.
+    pub(crate) builder: bool,
+
+    /// Whether [`TryFrom`] implementations from newer to older (adjacent)
+    /// versions should be generated.
+    pub(crate) downgrades: bool,
+
+    /// Whether the version enum should provide `upgrade_to_latest`, which
+    /// deserializes a value of any version and upgrades it to the latest one.
+    pub(crate) upgrade_to_latest: bool,
+
+    /// Whether a `#[cfg(test)]` helper asserting that conversions preserve the
+    /// fields should be generated for every version. This is only supported
+    /// for structs.
+    pub(crate) round_trip_tests: bool,
+}
+
+impl<I> VersionedContainer<I> {
+    /// Returns the latest (newest) declared version of this container.
+    ///
+    /// Versions are usually declared (and validated to be) in ascending order,
+    /// but with `options(allow_unsorted)` this is not guaranteed. That's why
+    /// the latest version is determined by comparing all declared versions.
+    pub(crate) fn latest_version(&self) -> &ContainerVersion {
+        self.versions
+            .iter()
+            .max_by_key(|v| v.inner)
+            .expect("internal error: container must declare at least one version")
+    }
+
+    /// Generates a type alias, like `FooLatest`, which always points to the
+    /// container definition of the latest declared version.
+    ///
+    /// Adding a new (newer) version automatically retargets the alias, which
+    /// enables code to reference the latest version without updating imports.
+    pub(crate) fn generate_latest_alias(&self) -> TokenStream {
+        let latest_version_ident = &self.latest_version().ident;
+        let alias_ident = format_ident!("{ident}Latest", ident = self.ident);
+        let (_, type_generics, _) = self.generics.split_for_impl();
+        let visibility = &self.visibility;
+        let generics = &self.generics;
+        let ident = &self.ident;
+
+        let doc =
+            format!("Type alias for the latest version (`{latest_version_ident}`) of `{ident}`.");
+
+        // The alias is generated for every container, so it is perfectly fine
+        // if it is not used. The latest version can additionally be marked as
+        // deprecated, which is also not a reason to emit a warning here. Bounds
+        // of generic parameters are not enforced on type aliases, but are
+        // forwarded as is to keep them visible in the docs.
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code, deprecated, type_alias_bounds)]
+            #visibility type #alias_ident #generics = #latest_version_ident::#ident #type_generics;
+        }
+    }
+
+    /// Generates the body of a [`From`] implementation between adjacent
+    /// versions, which converts the source object (named `from_ident`) using
+    /// the `conversion` expression.
+    ///
+    /// The conversion is wrapped by the hooks declared via
+    /// `#[versioned(hooks(pre_convert = "...", post_convert = "..."))]`. The
+    /// `pre_convert` hook receives the source object and is called before the
+    /// conversion, the `post_convert` hook receives the produced object.
+    pub(crate) fn generate_from_body(&self, conversion: TokenStream) -> TokenStream {
+        match self.generate_hook_calls() {
+            Some((pre_convert, post_convert)) => quote! {
+                #pre_convert
+                let converted = #conversion;
+                #post_convert
+                converted
+            },
+            None => conversion,
+        }
+    }
+
+    /// Generates the body of a [`TryFrom`] implementation between adjacent
+    /// versions (used for downgrades), which converts the source object using
+    /// the fallible `conversion` expression.
+    ///
+    /// The conversion is wrapped by the same hooks as the [`From`]
+    /// implementations, but the `post_convert` hook is only called if the
+    /// conversion succeeded.
+    pub(crate) fn generate_try_from_body(&self, conversion: TokenStream) -> TokenStream {
+        match self.generate_hook_calls() {
+            Some((pre_convert, post_convert)) => quote! {
+                #pre_convert
+                let converted = (#conversion)?;
+                #post_convert
+                ::std::result::Result::Ok(converted)
+            },
+            None => conversion,
+        }
+    }
+
+    /// Generates the calls of the `pre_convert` and `post_convert` hooks, or
+    /// [`None`] if no hooks are declared.
+    fn generate_hook_calls(&self) -> Option<(Option<TokenStream>, Option<TokenStream>)> {
+        let (pre_convert, post_convert) = match &self.hooks {
+            Some(hooks) => (hooks.pre_convert.as_ref(), hooks.post_convert.as_ref()),
+            None => (None, None),
+        };
+
+        if pre_convert.is_none() && post_convert.is_none() {
+            return None;
+        }
+
+        let from_ident = &self.from_ident;
+        let pre_convert = pre_convert.map(|pre_convert| {
+            quote! {
+                #pre_convert(&#from_ident);
+            }
+        });
+        let post_convert = post_convert.map(|post_convert| {
+            quote! {
+                #post_convert(&converted);
+            }
+        });
+
+        Some((pre_convert, post_convert))
+    }
+
+    /// Generates the [`From`] implementations spanning multiple versions, as
+    /// requested via `#[versioned(conversions = "...")]`. The implementations
+    /// between adjacent versions are generated per version instead.
+    ///
+    /// Each implementation chains the implementations of all adjacent versions
+    /// in between, which is why it is not generated if any of these versions
+    /// skips the generation of its `From` implementation.
+    pub(crate) fn generate_transitive_from_impls(&self) -> TokenStream {
+        let mut token_stream = TokenStream::new();
+
+        if self.skip_from || self.conversions == Conversions::Adjacent {
+            return token_stream;
+        }
+
+        let from_ident = &self.from_ident;
+        let ident = &self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let last_index = self.versions.len() - 1;
+
+        for (from_index, version) in self.versions.iter().enumerate() {
+            for to_index in from_index + 2..=last_index {
+                if self.conversions == Conversions::ToLatest && to_index != last_index {
+                    continue;
+                }
+
+                let path = &self.versions[from_index..=to_index];
+                if path[..path.len() - 1].iter().any(|v| v.skip_from) {
+                    continue;
+                }
+
+                let module_name = &version.ident;
+                let target_module_name = &self.versions[to_index].ident;
+
+                let steps = path[1..path.len() - 1].iter().map(|v| {
+                    let module_name = &v.ident;
+
+                    quote! {
+                        let #from_ident: #module_name::#ident #type_generics = #from_ident.into();
+                    }
+                });
+
+                token_stream.extend(quote! {
+                    #[automatically_derived]
+                    #[allow(deprecated)]
+                    impl #impl_generics From<#module_name::#ident #type_generics>
+                        for #target_module_name::#ident #type_generics #where_clause
+                    {
+                        fn from(#from_ident: #module_name::#ident #type_generics) -> Self {
+                            #(#steps)*
+                            #from_ident.into()
+                        }
+                    }
+                });
+            }
+        }
+
+        token_stream
+    }
+
+    /// Returns the ident of the generated version enum, like `FooVersion`.
+    pub(crate) fn version_enum_ident(&self) -> Ident {
+        format_ident!("{ident}Version", ident = self.ident)
+    }
+
+    /// Generates an enum, like `FooVersion`, with one variant per declared
+    /// version if requested via `#[versioned(version_enum)]`.
+    ///
+    /// The variants are ordered by the Kubernetes version precedence (even if
+    /// versions are declared unsorted), so that the derived [`Ord`]
+    /// implementation enables checks like `stored_version < latest_version`.
+    /// The enum can be converted from and to the version string via `FromStr`
+    /// and `as_str`.
+    pub(crate) fn generate_version_enum(&self) -> Option<TokenStream> {
+        if !self.version_enum {
+            return None;
+        }
+
+        let mut versions: Vec<_> = self.versions.iter().collect();
+        versions.sort_by_key(|v| v.inner);
+
+        let variants = versions.iter().map(|v| {
+            let doc = format!("Version `{version}`.", version = v.inner);
+            let variant_ident = &v.variant_ident;
+
+            quote! {
+                #[doc = #doc]
+                #variant_ident,
+            }
+        });
+
+        let as_str_arms = versions.iter().map(|v| {
+            let variant_ident = &v.variant_ident;
+            let version_str = v.inner.to_string();
+
+            quote! {
+                Self::#variant_ident => #version_str,
+            }
+        });
+
+        let from_str_arms = versions.iter().map(|v| {
+            let variant_ident = &v.variant_ident;
+            let version_str = v.inner.to_string();
+
+            quote! {
+                #version_str => ::std::result::Result::Ok(Self::#variant_ident),
+            }
+        });
+
+        let enum_ident = self.version_enum_ident();
+        let visibility = &self.visibility;
+        let doc = format!(
+            "All declared versions of `{ident}`, ordered by their Kubernetes version precedence.",
+            ident = self.ident
+        );
+
+        Some(quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+            #visibility enum #enum_ident {
+                #(#variants)*
+            }
+
+            #[automatically_derived]
+            impl #enum_ident {
+                /// Returns the version string, like `v1alpha1`.
+                pub fn as_str(&self) -> &'static str {
+                    match self {
+                        #(#as_str_arms)*
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::std::str::FromStr for #enum_ident {
+                type Err = ::std::string::String;
+
+                /// Parses a declared version string, like `v1alpha1`. Returns
+                /// the input as an error if it is not a declared version.
+                fn from_str(input: &str) -> ::std::result::Result<Self, Self::Err> {
+                    match input {
+                        #(#from_str_arms)*
+                        _ => ::std::result::Result::Err(input.to_owned()),
+                    }
+                }
+            }
+        })
+    }
+
+    /// Generates an enum, like `FooAnyVersion`, with one variant per declared
+    /// version, which holds the container of that version. It is only
+    /// generated alongside the version enum and if `From` implementations are
+    /// generated.
+    ///
+    /// The `try_into_latest` function upgrades the contained value along the
+    /// chain of generated `From` implementations (following the order of
+    /// declaration) and additionally returns the versions traversed. If the
+    /// `From` implementation of a version in the chain is skipped, the
+    /// variant of that version is returned as an error.
+    pub(crate) fn generate_any_version_enum(&self) -> Option<TokenStream> {
+        if !self.version_enum || self.skip_from {
+            return None;
+        }
+
+        let enum_ident = format_ident!("{ident}AnyVersion", ident = self.ident);
+        let version_enum_ident = self.version_enum_ident();
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let visibility = &self.visibility;
+        let generics = &self.generics;
+        let ident = &self.ident;
+
+        let latest_version_ident = &self.latest_version().ident;
+
+        let variants = self.versions.iter().map(|v| {
+            let variant_ident = &v.variant_ident;
+            let version_ident = &v.ident;
+
+            quote! {
+                #variant_ident(#version_ident::#ident #type_generics),
+            }
+        });
+
+        let version_arms = self.versions.iter().map(|v| {
+            let variant_ident = &v.variant_ident;
+
+            quote! {
+                Self::#variant_ident(_) => #version_enum_ident::#variant_ident,
+            }
+        });
+
+        let upgrade_arms = self.versions.iter().enumerate().map(|(index, v)| {
+            let variant_ident = &v.variant_ident;
+            let version_str = v.inner.to_string();
+
+            let mut steps = TokenStream::new();
+            for (version, next_version) in self.versions[index..]
+                .iter()
+                .zip(&self.versions[index + 1..])
+            {
+                if version.skip_from {
+                    let skipped_variant_ident = &version.variant_ident;
+
+                    return quote! {
+                        Self::#variant_ident(_) => ::std::result::Result::Err(
+                            #version_enum_ident::#skipped_variant_ident
+                        ),
+                    };
+                }
+
+                let next_version_ident = &next_version.ident;
+                let next_version_str = next_version.inner.to_string();
+
+                steps.extend(quote! {
+                    let value: #next_version_ident::#ident #type_generics = value.into();
+                    path.push(#next_version_str);
+                });
+            }
+
+            quote! {
+                Self::#variant_ident(value) => {
+                    let mut path = ::std::vec![#version_str];
+                    #steps
+                    ::std::result::Result::Ok((value, path))
+                }
+            }
+        });
+
+        let doc = format!(
+            "A `{ident}` of any declared version, which can be upgraded to the latest version."
+        );
+
+        Some(quote! {
+            #[doc = #doc]
+            #[allow(dead_code, deprecated)]
+            #visibility enum #enum_ident #generics #where_clause {
+                #(#variants)*
+            }
+
+            #[automatically_derived]
+            #[allow(dead_code, deprecated, unused_mut)]
+            impl #impl_generics #enum_ident #type_generics #where_clause {
+                /// Returns the version of the contained value.
+                pub fn version(&self) -> #version_enum_ident {
+                    match self {
+                        #(#version_arms)*
+                    }
+                }
+
+                /// Upgrades the contained value to the latest version by
+                /// chaining the generated `From` implementations. Returns the
+                /// upgraded value and the ordered list of versions traversed,
+                /// starting with the version of the contained value.
+                ///
+                /// Returns the version, which doesn't generate a `From`
+                /// implementation, if the chain is interrupted.
+                pub fn try_into_latest(
+                    self,
+                ) -> ::std::result::Result<
+                    (#latest_version_ident::#ident #type_generics, ::std::vec::Vec<&'static str>),
+                    #version_enum_ident,
+                > {
+                    match self {
+                        #(#upgrade_arms)*
+                    }
+                }
+            }
+        })
+    }
+
+    /// Generates the `upgrade_to_latest` function on the version enum if
+    /// requested via `#[versioned(upgrade_to_latest)]`. The function parses
+    /// the version string, deserializes the JSON value into the container of
+    /// that version and upgrades it using the `try_into_latest` function of
+    /// the enum holding any version (see [`Self::generate_any_version_enum`]).
+    pub(crate) fn generate_upgrade_to_latest_fn(&self) -> Option<TokenStream> {
+        if !self.upgrade_to_latest {
+            return None;
+        }
+
+        let any_version_enum_ident = format_ident!("{ident}AnyVersion", ident = self.ident);
+        let version_enum_ident = self.version_enum_ident();
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let ident = &self.ident;
+
+        let latest_version_ident = &self.latest_version().ident;
+
+        // Every version must be deserializable, which is why the where clause
+        // is extended by a bound per version.
+        let mut generics = self.generics.clone();
+        let where_clause = generics.make_where_clause();
+        for version in &self.versions {
+            let version_ident = &version.ident;
+
+            where_clause.predicates.push(parse_quote! {
+                #version_ident::#ident #type_generics: ::serde::de::DeserializeOwned
+            });
+        }
+
+        let deserialize_arms = self.versions.iter().map(|v| {
+            let variant_ident = &v.variant_ident;
+
+            quote! {
+                #version_enum_ident::#variant_ident => {
+                    #any_version_enum_ident::#variant_ident(::serde_json::from_value(value)?)
+                }
+            }
+        });
+
+        let unknown_version_message = format!(
+            "unknown version {{version:?}} of `{ident}`, expected one of {versions:?}",
+            versions = self
+                .versions
+                .iter()
+                .map(|v| v.inner.to_string())
+                .collect::<Vec<_>>()
+        );
+        let skipped_version_message =
+            format!("cannot upgrade `{ident}` from version {{version}}, because its `From` implementation is skipped");
+
+        Some(quote! {
+            #[automatically_derived]
+            #[allow(dead_code, deprecated)]
+            impl #version_enum_ident {
+                /// Deserializes `value` into the container of `version` and
+                /// upgrades it to the latest version by chaining the
+                /// generated `From` implementations.
+                ///
+                /// Returns an error if `version` is not a declared version,
+                /// `value` can't be deserialized or the chain of `From`
+                /// implementations is interrupted.
+                pub fn upgrade_to_latest #impl_generics (
+                    version: &str,
+                    value: ::serde_json::Value,
+                ) -> ::std::result::Result<#latest_version_ident::#ident #type_generics, ::serde_json::Error>
+                #where_clause
+                {
+                    let version: Self = version.parse().map_err(|version: ::std::string::String| {
+                        <::serde_json::Error as ::serde::de::Error>::custom(::std::format!(#unknown_version_message))
+                    })?;
+
+                    let value: #any_version_enum_ident #type_generics = match version {
+                        #(#deserialize_arms)*
+                    };
+
+                    value
+                        .try_into_latest()
+                        .map(|(value, _)| value)
+                        .map_err(|version| {
+                            let version = version.as_str();
+                            <::serde_json::Error as ::serde::de::Error>::custom(::std::format!(#skipped_version_message))
+                        })
+                }
+            }
+        })
+    }
+
+    /// Generates a function, like `foo_spec_crd_documents`, returning the
+    /// standalone CRD of every version as a YAML document if requested via
+    /// `#[versioned(k8s(kind = "...", crd_documents))]`.
+    ///
+    /// The custom resource of every version is derived from the container via
+    /// `#[derive(CustomResource)]`, which uses the same `version` for every
+    /// module, because the attribute is forwarded as is. The version name of
+    /// the CRD is thus replaced by the version of the module.
+    pub(crate) fn generate_crd_documents_fn(&self) -> Option<TokenStream> {
+        let kind = match &self.k8s {
+            Some(KubernetesAttributes {
+                kind: Some(kind),
+                crd_documents,
+                ..
+            }) if crd_documents.is_present() => kind,
+            _ => return None,
+        };
+
+        let fn_ident = format_ident!(
+            "{ident}_crd_documents",
+            ident = self.ident.to_string().to_case(Case::Snake)
+        );
+        let visibility = &self.visibility;
+
+        let documents = self.versions.iter().map(|v| {
+            let version_ident = &v.ident;
+            let version_str = v.inner.to_string();
+
+            quote! {
+                {
+                    let mut crd = <#version_ident::#kind as ::kube::CustomResourceExt>::crd();
+                    for version in &mut crd.spec.versions {
+                        version.name = #version_str.to_string();
+                    }
+
+                    ::serde_yaml::to_string(&crd).expect("the CRD must be serializable to YAML")
+                }
+            }
+        });
+
+        let doc = format!(
+            "Returns the standalone CRD of `{kind}` for every declared version (in the order of \
+            declaration) as a YAML document."
+        );
+
+        Some(quote! {
+            #[doc = #doc]
+            #[allow(dead_code, deprecated)]
+            #visibility fn #fn_ident() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#documents),*]
+            }
+        })
+    }
+
+    /// Generates the derive attribute for `arbitrary::Arbitrary` if requested
+    /// via `#[versioned(derive_arbitrary)]`.
+    pub(crate) fn generate_arbitrary_attr(&self) -> Option<TokenStream> {
+        self.derive_arbitrary
+            .then(|| quote! { #[derive(::arbitrary::Arbitrary)] })
+    }
+
+    /// Generates a `validate` function for the container of `version`, which
+    /// calls the validation function declared via `#[versioned(validate)]`.
+    ///
+    /// The impl block is generated outside of the version module, which is
+    /// why the path to the validation function is resolved relative to the
+    /// original container definition.
+    pub(crate) fn generate_validate_impl(&self, version: &ContainerVersion) -> TokenStream {
+        match &self.validate {
+            Some(ValidateAttributes { with, error }) => {
+                let version_ident = &version.ident;
+                let ident = &self.ident;
+                let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+                quote! {
+                    #[automatically_derived]
+                    #[allow(deprecated)]
+                    impl #impl_generics #version_ident::#ident #type_generics #where_clause {
+                        /// Validates this container by calling the validation
+                        /// function declared via `#[versioned(validate)]`.
+                        pub fn validate(&self) -> ::std::result::Result<(), #error> {
+                            #with(self)
+                        }
+                    }
+                }
+            }
+            None => quote! {},
+        }
+    }
+
+    /// Returns the Kubernetes `apiVersion` of the container of `version`. If
+    /// an API group is declared via `#[versioned(k8s(group))]`, it uses the
+    /// `<group>/<version>` format, otherwise only the version is used.
+    pub(crate) fn api_version(&self, version: &ContainerVersion) -> String {
+        match &self.k8s {
+            Some(k8s) => format!(
+                "{group}/{version}",
+                group = k8s.group,
+                version = version.inner
+            ),
+            None => version.inner.to_string(),
+        }
+    }
+
+    /// Generates an `API_VERSION` constant for the module of `version`, which
+    /// contains the `<group>/<version>` of the container. The constant is only
+    /// generated if an API group is declared via `#[versioned(k8s(group))]`.
+    pub(crate) fn generate_api_version_const(
+        &self,
+        version: &ContainerVersion,
+    ) -> Option<TokenStream> {
+        self.k8s.as_ref()?;
+
+        let api_version = self.api_version(version);
+        let doc = format!("The Kubernetes `apiVersion` of this version: `{api_version}`.");
+
+        // The constant is generated for every container, so it is perfectly
+        // fine if it is not used.
+        Some(quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub const API_VERSION: &str = #api_version;
+        })
+    }
+
+    /// Generates a `DEPRECATED` constant for the module of `version`, which
+    /// reports whether the version is marked as deprecated via
+    /// `#[versioned(version(name = "...", deprecated))]`. Reconcilers can use
+    /// it to warn about objects stored in a deprecated version.
+    pub(crate) fn generate_deprecated_const(&self, version: &ContainerVersion) -> TokenStream {
+        let deprecated = version.deprecated;
+
+        // The constant is generated for every container, so it is perfectly
+        // fine if it is not used.
+        quote! {
+            /// Whether this version is deprecated.
+            #[allow(dead_code)]
+            pub const DEPRECATED: bool = #deprecated;
+        }
+    }
+
+    /// Generates a `matches_version` function for the container of `version`,
+    /// which reports whether an `apiVersion` string matches the group and
+    /// version of the container.
+    pub(crate) fn generate_matches_version_impl(&self, version: &ContainerVersion) -> TokenStream {
+        let api_version = self.api_version(version);
+        let version_ident = &version.ident;
+        let ident = &self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let doc = format!("Returns whether the provided `api_version` matches `{api_version}`.");
+
+        // The function is generated for every container, so it is perfectly
+        // fine if it is not used.
+        quote! {
+            #[automatically_derived]
+            #[allow(dead_code, deprecated)]
+            impl #impl_generics #version_ident::#ident #type_generics #where_clause {
+                #[doc = #doc]
+                pub fn matches_version(api_version: &str) -> bool {
+                    api_version == #api_version
+                }
+            }
+        }
+    }
+
+    /// Generates a `spec_json_schema` function for the container of `version`
+    /// if requested via `#[versioned(k8s(spec_json_schema))]`.
+    ///
+    /// The function returns the JSON schema generated by `schemars`, which is
+    /// computed once and cached afterwards.
+    pub(crate) fn generate_spec_json_schema_impl(
+        &self,
+        version: &ContainerVersion,
+    ) -> Option<TokenStream> {
+        if !self
+            .k8s
+            .as_ref()
+            .is_some_and(|k8s| k8s.spec_json_schema.is_present())
+        {
+            return None;
+        }
+
+        let version_ident = &version.ident;
+        let ident = &self.ident;
+
+        let doc = format!(
+            "Returns the JSON schema of the `{version_ident}` version of `{ident}`, e.g. to \
+            validate objects in admission webhooks."
+        );
+
+        Some(quote! {
+            #[automatically_derived]
+            #[allow(dead_code, deprecated)]
+            impl #version_ident::#ident {
+                #[doc = #doc]
+                pub fn spec_json_schema() -> ::serde_json::Value {
+                    static SCHEMA: ::std::sync::OnceLock<::serde_json::Value> =
+                        ::std::sync::OnceLock::new();
+
+                    SCHEMA
+                        .get_or_init(|| {
+                            ::serde_json::to_value(::schemars::schema_for!(Self))
+                                .expect("the generated JSON schema must be serializable")
+                        })
+                        .clone()
+                }
+            }
+        })
+    }
+
+    /// Generates a `TryFrom<&DynamicObject>` implementation for the container
+    /// of `version` if requested via `#[versioned(k8s(try_from_dynamic_object))]`.
+    ///
+    /// The implementation deserializes the `spec` of the object, but only if
+    /// the `apiVersion` of the object matches the container of `version`.
+    /// Otherwise, objects of other versions would silently be deserialized
+    /// into the wrong version.
+    pub(crate) fn generate_try_from_dynamic_object_impl(
+        &self,
+        version: &ContainerVersion,
+    ) -> Option<TokenStream> {
+        if !self
+            .k8s
+            .as_ref()
+            .is_some_and(|k8s| k8s.try_from_dynamic_object.is_present())
+        {
+            return None;
+        }
+
+        let api_version = self.api_version(version);
+        let version_ident = &version.ident;
+        let ident = &self.ident;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+
+        // The spec is deserialized, which generic containers only support if
+        // their generic parameters do. That's why the where clause is extended
+        // by a bound for the container of this version.
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.push(parse_quote! {
+            #version_ident::#ident #type_generics: ::serde::de::DeserializeOwned
+        });
+        let where_clause = &generics.where_clause;
+
+        Some(quote! {
+            #[automatically_derived]
+            #[allow(deprecated)]
+            impl #impl_generics ::std::convert::TryFrom<&::kube::api::DynamicObject>
+                for #version_ident::#ident #type_generics #where_clause
+            {
+                type Error = ::serde_json::Error;
+
+                fn try_from(
+                    object: &::kube::api::DynamicObject,
+                ) -> ::std::result::Result<Self, Self::Error> {
+                    let api_version = object.types.as_ref().map(|types| types.api_version.as_str());
+
+                    if api_version != ::std::option::Option::Some(#api_version) {
+                        return ::std::result::Result::Err(
+                            <::serde_json::Error as ::serde::de::Error>::custom(::std::format!(
+                                "expected apiVersion {:?}, got {:?}",
+                                #api_version,
+                                api_version,
+                            )),
+                        );
+                    }
+
+                    let spec = object
+                        .data
+                        .get("spec")
+                        .cloned()
+                        .unwrap_or(::serde_json::Value::Null);
+
+                    ::serde_json::from_value(spec)
+                }
+            }
+        })
+    }
 }
@@ -1,7 +1,14 @@
-use std::{collections::BTreeMap, marker::PhantomData, ops::Deref};
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    ops::{Bound, Deref},
+};
 
+use k8s_version::Version;
 use quote::format_ident;
-use syn::{spanned::Spanned, Attribute, Ident, Path};
+use syn::{
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Ident, Meta, Path, Token,
+};
 
 use crate::{
     attrs::common::{ContainerAttributes, ItemAttributes, ValidateVersions},
@@ -277,7 +284,13 @@ where
                             ItemStatus::Added { ident, .. } => ident,
                             ItemStatus::Renamed { to, .. } => to,
                             ItemStatus::NoChange(ident) => ident,
-                            _ => unreachable!(),
+                            // The item is added in a later version, which is
+                            // why it isn't present in any version before.
+                            ItemStatus::NotPresent => {
+                                chain.insert(version.inner, ItemStatus::NotPresent);
+                                continue;
+                            }
+                            ItemStatus::Deprecated { .. } => unreachable!(),
                         };
 
                         chain.insert(version.inner, ItemStatus::NoChange(ident.clone()))
@@ -299,6 +312,74 @@ where
     }
 }
 
+impl<I, A> VersionedItem<I, A>
+where
+    A: for<'i> TryFrom<&'i I> + Attributes,
+    I: Named + Spanned,
+{
+    /// Returns the original attributes, which are forwarded onto the item in
+    /// `version`.
+    ///
+    /// A `#[serde(rename)]` refers to the declared (latest) name of the item.
+    /// In versions which use a different name, because the item is renamed
+    /// later on, the rename would conflict with the versioned name and is thus
+    /// removed. All other attributes are forwarded as is.
+    pub(crate) fn forwarded_attributes(&self, version: &ContainerVersion) -> Vec<Attribute> {
+        let renamed_later = self.chain.as_ref().is_some_and(|chain| {
+            chain
+                .range((Bound::Excluded(version.inner), Bound::Unbounded))
+                .any(|(_, status)| matches!(status, ItemStatus::Renamed { .. }))
+        });
+
+        if !renamed_later {
+            return self.original_attributes.clone();
+        }
+
+        self.original_attributes
+            .iter()
+            .filter_map(remove_serde_rename)
+            .collect()
+    }
+
+    /// Returns the version the item was deprecated in and the deprecation
+    /// note, if the item is deprecated in or before `version`.
+    pub(crate) fn deprecation(&self, version: &ContainerVersion) -> Option<(&Version, &str)> {
+        self.chain
+            .as_ref()?
+            .range(..=version.inner)
+            .find_map(|(since, status)| match status {
+                ItemStatus::Deprecated { note, .. } => Some((since, note.as_str())),
+                _ => None,
+            })
+    }
+}
+
+/// Removes `rename` from a `#[serde(...)]` attribute. Returns [`None`] if the
+/// attribute is empty afterwards. Other attributes are returned as is.
+fn remove_serde_rename(attribute: &Attribute) -> Option<Attribute> {
+    if !attribute.path().is_ident("serde") {
+        return Some(attribute.clone());
+    }
+
+    // Attributes which can't be parsed are forwarded as is, so that serde can
+    // report a proper error.
+    let Ok(metas) = attribute.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+    else {
+        return Some(attribute.clone());
+    };
+
+    let metas: Punctuated<Meta, Token![,]> = metas
+        .into_iter()
+        .filter(|meta| !meta.path().is_ident("rename"))
+        .collect();
+
+    if metas.is_empty() {
+        return None;
+    }
+
+    Some(parse_quote! { #[serde(#metas)] })
+}
+
 #[derive(Debug)]
 pub(crate) enum ItemStatus {
     Added {
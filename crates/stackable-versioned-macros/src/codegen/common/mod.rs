@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use darling::util::SpannedValue;
 use k8s_version::Version;
 use proc_macro2::Span;
 use quote::format_ident;
@@ -33,6 +34,10 @@ pub(crate) struct ContainerVersion {
     /// The ident of the container.
     pub(crate) ident: Ident,
 
+    /// The ident of the variant representing this version in the generated
+    /// version enum, like `V1Alpha1`.
+    pub(crate) variant_ident: Ident,
+
     /// Store additional doc-comment lines for this version.
     pub(crate) version_specific_docs: Vec<String>,
 }
@@ -62,19 +67,61 @@ impl From<&ContainerAttributes> for Vec<ContainerVersion> {
             .map(|v| ContainerVersion {
                 skip_from: v.skip.as_ref().map_or(false, |s| s.from.is_present()),
                 ident: Ident::new(&v.name.to_string(), Span::call_site()),
+                variant_ident: format_version_variant_ident(&v.name),
                 deprecated: v.deprecated.is_present(),
-                inner: v.name,
+                inner: *v.name,
                 version_specific_docs: process_docs(&v.doc),
             })
             .collect()
     }
 }
 
+/// Returns the ident of the version enum variant for `version`. The first
+/// character as well as every character following a digit is uppercased, which
+/// turns `v1alpha1` into `V1Alpha1`.
+pub(crate) fn format_version_variant_ident(version: &Version) -> Ident {
+    let mut variant = String::new();
+    let mut uppercase_next = true;
+
+    for chr in version.to_string().chars() {
+        if uppercase_next {
+            variant.extend(chr.to_uppercase());
+        } else {
+            variant.push(chr);
+        }
+
+        uppercase_next = chr.is_ascii_digit();
+    }
+
+    Ident::new(&variant, Span::call_site())
+}
+
 /// Returns the container ident used in [`From`] implementations.
 pub(crate) fn format_container_from_ident(ident: &Ident) -> Ident {
     format_ident!("__sv_{ident}", ident = ident.to_string().to_lowercase())
 }
 
+/// Returns the container ident used in [`From`] implementations, which is
+/// either the user-provided `from_name` or derived from the container ident.
+/// See [`format_container_from_ident`].
+pub(crate) fn container_from_ident(
+    ident: &Ident,
+    from_name: Option<&SpannedValue<String>>,
+) -> syn::Result<Ident> {
+    match from_name {
+        Some(from_name) => syn::parse_str::<Ident>(from_name).map_err(|_| {
+            syn::Error::new(
+                from_name.span(),
+                format!(
+                    "`from_name` must be a valid identifier, got `{from_name}`",
+                    from_name = from_name.as_str()
+                ),
+            )
+        }),
+        None => Ok(format_container_from_ident(ident)),
+    }
+}
+
 /// Removes the deprecated prefix from a field ident.
 ///
 /// See [`DEPRECATED_FIELD_PREFIX`].
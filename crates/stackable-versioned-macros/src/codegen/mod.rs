@@ -34,6 +34,7 @@ pub(crate) fn expand(attributes: ContainerAttributes, input: DeriveInput) -> Res
                 original_attributes: input.attrs,
                 visibility: input.vis,
                 ident: input.ident,
+                generics: input.generics,
             };
 
             VersionedStruct::new(input, data, attributes)?.generate_tokens()
@@ -43,6 +44,7 @@ pub(crate) fn expand(attributes: ContainerAttributes, input: DeriveInput) -> Res
                 original_attributes: input.attrs,
                 visibility: input.vis,
                 ident: input.ident,
+                generics: input.generics,
             };
 
             VersionedEnum::new(input, data, attributes)?.generate_tokens()
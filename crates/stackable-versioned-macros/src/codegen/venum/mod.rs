@@ -9,7 +9,7 @@ use crate::{
     attrs::common::ContainerAttributes,
     codegen::{
         common::{
-            format_container_from_ident, Container, ContainerInput, ContainerVersion, Item,
+            container_from_ident, Container, ContainerInput, ContainerVersion, Item,
             VersionedContainer,
         },
         venum::variant::VersionedVariant,
@@ -43,8 +43,38 @@ impl Container<DataEnum, VersionedVariant> for VersionedEnum {
             original_attributes,
             visibility,
             ident,
+            generics,
         } = input;
 
+        if attributes.builder.is_present() {
+            return Err(Error::new(
+                attributes.builder.span(),
+                "builders are only supported for structs",
+            ));
+        }
+
+        if attributes.round_trip_tests.is_present() {
+            return Err(Error::new(
+                attributes.round_trip_tests.span(),
+                "round-trip tests are only supported for structs",
+            ));
+        }
+
+        // The JSON schema is cached in a static, which is shared by all
+        // instantiations of a generic container.
+        if !generics.params.is_empty() {
+            if let Some(k8s) = attributes
+                .k8s
+                .as_ref()
+                .filter(|k8s| k8s.spec_json_schema.is_present())
+            {
+                return Err(Error::new(
+                    k8s.spec_json_schema.span(),
+                    "`spec_json_schema` is not supported for generic containers",
+                ));
+            }
+        }
+
         // Convert the raw version attributes into a container version.
         let versions: Vec<_> = (&attributes).into();
 
@@ -77,19 +107,30 @@ impl Container<DataEnum, VersionedVariant> for VersionedEnum {
             }
         }
 
-        let from_ident = format_container_from_ident(&ident);
+        let from_ident = container_from_ident(&ident, attributes.from_name.as_ref())?;
 
         Ok(Self(VersionedContainer {
             skip_from: attributes
                 .options
                 .skip
                 .map_or(false, |s| s.from.is_present()),
+            derive_arbitrary: attributes.derive_arbitrary.is_present(),
+            validate: attributes.validate,
+            k8s: attributes.k8s,
+            version_enum: attributes.version_enum.is_present(),
+            conversions: attributes.conversions,
+            hooks: attributes.hooks,
+            builder: false,
+            round_trip_tests: false,
+            downgrades: attributes.downgrades.is_present(),
+            upgrade_to_latest: attributes.upgrade_to_latest.is_present(),
             original_attributes,
             visibility,
             from_ident,
             versions,
             items,
             ident,
+            generics,
         }))
     }
 
@@ -101,6 +142,13 @@ impl Container<DataEnum, VersionedVariant> for VersionedEnum {
             token_stream.extend(self.generate_version(version, versions.peek().copied()));
         }
 
+        token_stream.extend(self.generate_transitive_from_impls());
+        token_stream.extend(self.generate_latest_alias());
+        token_stream.extend(self.generate_version_enum());
+        token_stream.extend(self.generate_any_version_enum());
+        token_stream.extend(self.generate_upgrade_to_latest_fn());
+        token_stream.extend(self.generate_crd_documents_fn());
+
         token_stream
     }
 }
@@ -116,6 +164,8 @@ impl VersionedEnum {
         let original_attributes = &self.original_attributes;
         let visibility = &self.visibility;
         let enum_name = &self.ident;
+        let generics = &self.generics;
+        let where_clause = &self.generics.where_clause;
 
         // Generate variants of the enum for `version`.
         let variants = self.generate_enum_variants(version);
@@ -145,24 +195,50 @@ impl VersionedEnum {
             })
         }
 
+        let arbitrary_attr = self.generate_arbitrary_attr();
+        let api_version_const = self.generate_api_version_const(version);
+        let deprecated_const = self.generate_deprecated_const(version);
+
         // Generate tokens for the module and the contained enum
         token_stream.extend(quote! {
             #[automatically_derived]
             #deprecated_attr
             #visibility mod #version_ident {
+                // Items are declared in the scope of the original container,
+                // which is why their types (and paths) must resolve here too.
+                #[allow(unused_imports)]
+                use super::*;
+
                 #(#original_attributes)*
+                #arbitrary_attr
                 #version_specific_docs
-                pub enum #enum_name {
+                pub enum #enum_name #generics #where_clause {
                     #variants
                 }
+
+                #api_version_const
+                #deprecated_const
             }
         });
 
         // Generate the From impl between this `version` and the next one.
         if !self.skip_from && !version.skip_from {
             token_stream.extend(self.generate_from_impl(version, next_version));
+            token_stream.extend(self.generate_downgrade_impl(version, next_version));
         }
 
+        // Generate the validate function for this `version`.
+        token_stream.extend(self.generate_validate_impl(version));
+
+        // Generate the matches_version function for this `version`.
+        token_stream.extend(self.generate_matches_version_impl(version));
+
+        // Generate the TryFrom<&DynamicObject> impl for this `version`.
+        token_stream.extend(self.generate_try_from_dynamic_object_impl(version));
+
+        // Generate the spec_json_schema function for this `version`.
+        token_stream.extend(self.generate_spec_json_schema_impl(version));
+
         token_stream
     }
 
@@ -187,6 +263,7 @@ impl VersionedEnum {
 
             let from_ident = &self.from_ident;
             let enum_ident = &self.ident;
+            let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
 
             let mut variants = TokenStream::new();
 
@@ -200,16 +277,25 @@ impl VersionedEnum {
                 ))
             }
 
+            let body = self.generate_from_body(quote! {
+                match #from_ident {
+                    #variants
+                }
+            });
+
             // TODO (@Techassi): Be a little bit more clever about when to include
             // the #[allow(deprecated)] attribute.
+
+            // The data of variants is converted using `Into`, which is a no-op
+            // for data not changing between versions.
             return quote! {
                 #[automatically_derived]
-                #[allow(deprecated)]
-                impl From<#module_name::#enum_ident> for #next_module_name::#enum_ident {
-                    fn from(#from_ident: #module_name::#enum_ident) -> Self {
-                        match #from_ident {
-                            #variants
-                        }
+                #[allow(deprecated, clippy::useless_conversion)]
+                impl #impl_generics From<#module_name::#enum_ident #type_generics>
+                    for #next_module_name::#enum_ident #type_generics #where_clause
+                {
+                    fn from(#from_ident: #module_name::#enum_ident #type_generics) -> Self {
+                        #body
                     }
                 }
             };
@@ -217,4 +303,58 @@ impl VersionedEnum {
 
         quote! {}
     }
+
+    /// Generates a [`TryFrom`] implementation, which downgrades the enum of
+    /// `next_version` to `version`, if requested via `#[versioned(downgrades)]`.
+    ///
+    /// Variants added in `next_version` have no representation in `version`,
+    /// which is why downgrading such a variant returns its name as an error.
+    fn generate_downgrade_impl(
+        &self,
+        version: &ContainerVersion,
+        next_version: Option<&ContainerVersion>,
+    ) -> Option<TokenStream> {
+        let next_version = next_version.filter(|_| self.downgrades)?;
+
+        let next_module_name = &next_version.ident;
+        let module_name = &version.ident;
+
+        let from_ident = &self.from_ident;
+        let enum_ident = &self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let mut variants = TokenStream::new();
+
+        for item in &self.items {
+            variants.extend(item.generate_for_downgrade_impl(
+                module_name,
+                next_module_name,
+                version,
+                next_version,
+                enum_ident,
+            ))
+        }
+
+        let body = self.generate_try_from_body(quote! {
+            match #from_ident {
+                #variants
+            }
+        });
+
+        Some(quote! {
+            #[automatically_derived]
+            #[allow(deprecated, clippy::useless_conversion)]
+            impl #impl_generics TryFrom<#next_module_name::#enum_ident #type_generics>
+                for #module_name::#enum_ident #type_generics #where_clause
+            {
+                type Error = &'static str;
+
+                fn try_from(
+                    #from_ident: #next_module_name::#enum_ident #type_generics,
+                ) -> ::std::result::Result<Self, Self::Error> {
+                    #body
+                }
+            }
+        })
+    }
 }
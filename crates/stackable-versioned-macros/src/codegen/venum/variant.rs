@@ -2,8 +2,8 @@ use std::ops::{Deref, DerefMut};
 
 use darling::FromVariant;
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Ident, Variant};
+use quote::{format_ident, quote};
+use syn::{Fields, Ident, Variant};
 
 use crate::{
     attrs::{
@@ -92,7 +92,13 @@ impl VersionedVariant {
         &self,
         container_version: &ContainerVersion,
     ) -> Option<TokenStream> {
-        let original_attributes = &self.original_attributes;
+        let original_attributes = self.forwarded_attributes(container_version);
+        let fields = &self.inner.fields;
+
+        // Deprecated variants stay deprecated in all later versions.
+        let deprecated_attribute = self
+            .deprecation(container_version)
+            .map(|(_, note)| quote! { #[deprecated = #note] });
 
         match &self.chain {
             // NOTE (@Techassi): https://rust-lang.github.io/rust-clippy/master/index.html#/expect_fun_call
@@ -104,20 +110,21 @@ impl VersionedVariant {
             }) {
                 ItemStatus::Added { ident, .. } => Some(quote! {
                     #(#original_attributes)*
-                    #ident,
+                    #ident #fields,
                 }),
                 ItemStatus::Renamed { to, .. } => Some(quote! {
                     #(#original_attributes)*
-                    #to,
+                    #to #fields,
                 }),
                 ItemStatus::Deprecated { ident, .. } => Some(quote! {
                     #(#original_attributes)*
-                    #[deprecated]
-                    #ident,
+                    #deprecated_attribute
+                    #ident #fields,
                 }),
                 ItemStatus::NoChange(ident) => Some(quote! {
                     #(#original_attributes)*
-                    #ident,
+                    #deprecated_attribute
+                    #ident #fields,
                 }),
                 ItemStatus::NotPresent => None,
             },
@@ -129,13 +136,14 @@ impl VersionedVariant {
 
                 Some(quote! {
                     #(#original_attributes)*
-                    #variant_ident,
+                    #variant_ident #fields,
                 })
             }
         }
     }
 
-    /// Generates tokens to be used in a [`From`] implementation.
+    /// Generates tokens to be used in a [`From`] implementation. The data of
+    /// variants carrying data is converted using [`Into`].
     pub(crate) fn generate_for_from_impl(
         &self,
         module_name: &Ident,
@@ -144,12 +152,15 @@ impl VersionedVariant {
         next_version: &ContainerVersion,
         enum_ident: &Ident,
     ) -> TokenStream {
+        let (pattern, conversion) = self.generate_data_conversion();
+
         match &self.chain {
             Some(chain) => match (
                 chain.get_expect(&version.inner),
                 chain.get_expect(&next_version.inner),
             ) {
-                (_, ItemStatus::Added { .. }) => quote! {},
+                (_, ItemStatus::Added { .. })
+                | (ItemStatus::NotPresent, ItemStatus::NotPresent) => quote! {},
                 (old, next) => {
                     let old_variant_ident = old
                         .get_ident()
@@ -159,7 +170,7 @@ impl VersionedVariant {
                         .expect("internal error: next variant must have a name");
 
                     quote! {
-                        #module_name::#enum_ident::#old_variant_ident => #next_module_name::#enum_ident::#next_variant_ident,
+                        #module_name::#enum_ident::#old_variant_ident #pattern => #next_module_name::#enum_ident::#next_variant_ident #conversion,
                     }
                 }
             },
@@ -167,9 +178,90 @@ impl VersionedVariant {
                 let variant_ident = &self.inner.ident;
 
                 quote! {
-                    #module_name::#enum_ident::#variant_ident => #next_module_name::#enum_ident::#variant_ident,
+                    #module_name::#enum_ident::#variant_ident #pattern => #next_module_name::#enum_ident::#variant_ident #conversion,
+                }
+            }
+        }
+    }
+
+    /// Generates tokens to be used in a [`TryFrom`] implementation, which
+    /// downgrades the enum from `next_version` to `version`. Variants added in
+    /// `next_version` have no representation in `version`, which is why their
+    /// name is returned as an error.
+    pub(crate) fn generate_for_downgrade_impl(
+        &self,
+        module_name: &Ident,
+        next_module_name: &Ident,
+        version: &ContainerVersion,
+        next_version: &ContainerVersion,
+        enum_ident: &Ident,
+    ) -> TokenStream {
+        let (pattern, conversion) = self.generate_data_conversion();
+
+        match &self.chain {
+            Some(chain) => {
+                let Some(next_variant_ident) = chain.get_expect(&next_version.inner).get_ident()
+                else {
+                    return quote! {};
+                };
+
+                match chain.get_expect(&version.inner).get_ident() {
+                    Some(old_variant_ident) => quote! {
+                        #next_module_name::#enum_ident::#next_variant_ident #pattern => ::std::result::Result::Ok(#module_name::#enum_ident::#old_variant_ident #conversion),
+                    },
+                    None => {
+                        let next_variant_name = next_variant_ident.to_string();
+
+                        quote! {
+                            #next_module_name::#enum_ident::#next_variant_ident { .. } => ::std::result::Result::Err(#next_variant_name),
+                        }
+                    }
+                }
+            }
+            None => {
+                let variant_ident = &self.inner.ident;
+
+                quote! {
+                    #next_module_name::#enum_ident::#variant_ident #pattern => ::std::result::Result::Ok(#module_name::#enum_ident::#variant_ident #conversion),
                 }
             }
         }
     }
+
+    /// Generates the pattern destructuring the data of the variant and the
+    /// expression constructing the data of the variant in another version.
+    /// Each field is converted using [`Into`], which enables nested versioned
+    /// types. Both are empty for unit variants.
+    fn generate_data_conversion(&self) -> (TokenStream, TokenStream) {
+        match &self.inner.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> = fields
+                    .named
+                    .iter()
+                    .map(|field| {
+                        field
+                            .ident
+                            .as_ref()
+                            .expect("internal error: named field must have an ident")
+                    })
+                    .collect();
+
+                (
+                    quote! { { #(#idents),* } },
+                    quote! { { #(#idents: #idents.into()),* } },
+                )
+            }
+            Fields::Unnamed(fields) => {
+                let idents: Vec<_> = (0..fields.unnamed.len())
+                    .map(|index| format_ident!("__sv_{index}"))
+                    .collect();
+
+                (
+                    quote! { ( #(#idents),* ) },
+                    quote! { ( #(#idents.into()),* ) },
+                )
+            }
+            Fields::Unit => (TokenStream::new(), TokenStream::new()),
+        }
+    }
 }
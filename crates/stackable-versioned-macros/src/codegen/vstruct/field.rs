@@ -1,18 +1,23 @@
 use std::ops::{Deref, DerefMut};
 
 use darling::FromField;
+use k8s_version::Version;
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Field, Ident};
+use quote::{quote, ToTokens};
+use syn::{Error, Field, Ident, Type};
 
 use crate::{
     attrs::{
         common::{ContainerAttributes, ItemAttributes},
-        field::FieldAttributes,
+        field::{ChangedAttributes, FieldAttributes},
     },
-    codegen::common::{
-        remove_deprecated_field_prefix, Attributes, ContainerVersion, Item, ItemStatus, Named,
-        VersionedItem,
+    codegen::{
+        chain::BTreeMapExt,
+        common::{
+            remove_deprecated_field_prefix, Attributes, ContainerVersion, Item, ItemStatus, Named,
+            VersionedItem,
+        },
+        vstruct::option_inner_type,
     },
 };
 
@@ -25,19 +30,25 @@ use crate::{
 /// Additionally, the [`Field`] data can be used to forward attributes, generate
 /// documentation, etc.
 #[derive(Debug)]
-pub(crate) struct VersionedField(VersionedItem<Field, FieldAttributes>);
+pub(crate) struct VersionedField {
+    item: VersionedItem<Field, FieldAttributes>,
+
+    /// The type changes of the field, sorted by version. Before the first
+    /// change, the field uses the `from_type` of that change.
+    pub(crate) changes: Vec<ChangedAttributes>,
+}
 
 impl Deref for VersionedField {
     type Target = VersionedItem<Field, FieldAttributes>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.item
     }
 }
 
 impl DerefMut for VersionedField {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.item
     }
 }
 
@@ -85,8 +96,78 @@ impl VersionedField {
         field: Field,
         container_attributes: &ContainerAttributes,
     ) -> syn::Result<Self> {
+        // The field attributes are consumed while creating the versioned item,
+        // which is why the field specific type changes are extracted upfront.
+        let mut changes = FieldAttributes::try_from(&field)?.changes;
+
+        for change in &changes {
+            if !container_attributes
+                .versions
+                .iter()
+                .any(|v| *v.name == *change.since)
+            {
+                return Err(Error::new(
+                    change.since.span(),
+                    "field action `changed` uses version which was not declared via #[versioned(version)]",
+                ));
+            }
+        }
+
+        changes.sort_by_key(|change| *change.since);
+
         let item = VersionedItem::<_, FieldAttributes>::new(field, container_attributes)?;
-        Ok(Self(item))
+        let field = Self { item, changes };
+
+        // Without a conversion function, the value can only be converted if
+        // the field becomes optional or required.
+        for change in &field.changes {
+            if change.with.is_none() && field.option_change(change).is_none() {
+                return Err(Error::new(
+                    change.since.span(),
+                    "field action `changed` requires `with`, unless the field only becomes optional (`T` to `Option<T>`) or required (`Option<T>` to `T`)",
+                ));
+            }
+        }
+
+        Ok(field)
+    }
+
+    /// Returns the type of the field in `version`. This is the `from_type` of
+    /// the first type change after `version`, or the declared type if the type
+    /// doesn't change anymore.
+    pub(crate) fn field_type(&self, version: &ContainerVersion) -> &Type {
+        self.type_in(&version.inner)
+    }
+
+    fn type_in(&self, version: &Version) -> &Type {
+        self.changes
+            .iter()
+            .find(|change| *change.since > *version)
+            .map_or(&self.inner.ty, |change| &change.from_type)
+    }
+
+    /// Returns whether the type `change` only makes the field optional or
+    /// required, which is converted without a conversion function.
+    pub(crate) fn option_change(&self, change: &ChangedAttributes) -> Option<OptionChange> {
+        let from_type = &change.from_type;
+        let to_type = self.type_in(&change.since);
+
+        if option_inner_type(to_type).is_some_and(|inner| same_type(inner, from_type)) {
+            return Some(OptionChange::Wrap);
+        }
+
+        if option_inner_type(from_type).is_some_and(|inner| same_type(inner, to_type)) {
+            return Some(OptionChange::Unwrap);
+        }
+
+        None
+    }
+
+    /// Returns the type change which happened in exactly `version`, if any.
+    pub(crate) fn change_in(&self, version: &ContainerVersion) -> Option<&ChangedAttributes> {
+        self.changes
+            .iter()
+            .find(|change| *change.since == version.inner)
     }
 
     /// Generates tokens to be used in a container definition.
@@ -94,7 +175,7 @@ impl VersionedField {
         &self,
         container_version: &ContainerVersion,
     ) -> Option<TokenStream> {
-        let original_attributes = &self.original_attributes;
+        let original_attributes = self.forwarded_attributes(container_version);
 
         match &self.chain {
             Some(chain) => {
@@ -106,7 +187,22 @@ impl VersionedField {
                 // The code generation then depends on the relation to other
                 // versions (with actions).
 
-                let field_type = &self.inner.ty;
+                let field_type = self.field_type(container_version);
+
+                // Deprecated fields stay deprecated in all later versions. A
+                // note is additionally appended to the (forwarded) docs of the
+                // field, because the deprecation is otherwise only visible as
+                // a compiler warning.
+                let deprecated_attributes =
+                    self.deprecation(container_version).map(|(since, note)| {
+                        let deprecated_doc = format!("Deprecated since `{since}`: {note}");
+
+                        quote! {
+                            #[doc = ""]
+                            #[doc = #deprecated_doc]
+                            #[deprecated = #note]
+                        }
+                    });
 
                 // NOTE (@Techassi): https://rust-lang.github.io/rust-clippy/master/index.html#/expect_fun_call
                 match chain.get(&container_version.inner).unwrap_or_else(|| {
@@ -124,17 +220,16 @@ impl VersionedField {
                         pub #to: #field_type,
                     }),
                     ItemStatus::Deprecated {
-                        ident: field_ident,
-                        note,
-                        ..
+                        ident: field_ident, ..
                     } => Some(quote! {
                         #(#original_attributes)*
-                        #[deprecated = #note]
+                        #deprecated_attributes
                         pub #field_ident: #field_type,
                     }),
                     ItemStatus::NotPresent => None,
                     ItemStatus::NoChange(field_ident) => Some(quote! {
                         #(#original_attributes)*
+                        #deprecated_attributes
                         pub #field_ident: #field_type,
                     }),
                 }
@@ -143,7 +238,7 @@ impl VersionedField {
                 // If there is no chain of field actions, the field is not
                 // versioned and therefore included in all versions.
                 let field_ident = &self.inner.ident;
-                let field_type = &self.inner.ty;
+                let field_type = self.field_type(container_version);
 
                 Some(quote! {
                     #(#original_attributes)*
@@ -153,7 +248,9 @@ impl VersionedField {
         }
     }
 
-    /// Generates tokens to be used in a [`From`] implementation.
+    /// Generates tokens to be used in a [`From`] implementation. The value of
+    /// a field whose type changed in `next_version` is converted using the
+    /// declared conversion function.
     pub(crate) fn generate_for_from_impl(
         &self,
         version: &ContainerVersion,
@@ -173,6 +270,9 @@ impl VersionedField {
                     (_, ItemStatus::Added { ident, default_fn }) => quote! {
                         #ident: #default_fn(),
                     },
+                    // The field is added in a later version and thus not
+                    // present in either of the two versions.
+                    (ItemStatus::NotPresent, ItemStatus::NotPresent) => quote! {},
                     (old, next) => {
                         let old_field_ident = old
                             .get_ident()
@@ -182,18 +282,132 @@ impl VersionedField {
                             .get_ident()
                             .expect("internal error: new field must have a name");
 
+                        let value = self
+                            .convert_value(quote! { #from_ident.#old_field_ident }, next_version);
+
                         quote! {
-                            #next_field_ident: #from_ident.#old_field_ident,
+                            #next_field_ident: #value,
                         }
                     }
                 }
             }
             None => {
                 let field_ident = &self.inner.ident;
+                let value = self.convert_value(quote! { #from_ident.#field_ident }, next_version);
+
                 quote! {
-                    #field_ident: #from_ident.#field_ident,
+                    #field_ident: #value,
                 }
             }
         }
     }
+
+    /// Generates tokens to be used in a [`TryFrom`] implementation, which
+    /// downgrades the container from `next_version` to `version`. Fields not
+    /// present in `version` (because they were added in `next_version`) are
+    /// dropped.
+    pub(crate) fn generate_for_downgrade_impl(
+        &self,
+        version: &ContainerVersion,
+        next_version: &ContainerVersion,
+        from_ident: &Ident,
+    ) -> Option<TokenStream> {
+        match &self.chain {
+            Some(chain) => {
+                let old_field_ident = chain.get_expect(&version.inner).get_ident()?;
+                let next_field_ident = chain
+                    .get_expect(&next_version.inner)
+                    .get_ident()
+                    .expect("internal error: next field must have a name");
+
+                let value =
+                    self.downgrade_value(quote! { #from_ident.#next_field_ident }, next_version);
+
+                Some(quote! {
+                    #old_field_ident: #value,
+                })
+            }
+            None => {
+                let field_ident = &self.inner.ident;
+                let value = self.downgrade_value(quote! { #from_ident.#field_ident }, next_version);
+
+                Some(quote! {
+                    #field_ident: #value,
+                })
+            }
+        }
+    }
+
+    /// Wraps `value` in a call to the `with` function, if the type of the
+    /// field changed in `next_version`. Without a `with` function, the value
+    /// is wrapped in [`Some`] or unwrapped (panicking if it is not set).
+    fn convert_value(&self, value: TokenStream, next_version: &ContainerVersion) -> TokenStream {
+        let Some(change) = self.change_in(next_version) else {
+            return value;
+        };
+
+        if let Some(with) = &change.with {
+            return quote! { #with(#value) };
+        }
+
+        match self.option_change(change) {
+            Some(OptionChange::Wrap) => quote! { ::std::option::Option::Some(#value) },
+            Some(OptionChange::Unwrap) => {
+                let message = format!(
+                    "field `{ident}` must be set to convert it to version `{version}`",
+                    ident = self.inner.cleaned_ident(),
+                    version = next_version.inner
+                );
+
+                quote! { #value.expect(#message) }
+            }
+            None => unreachable!("internal error: changed field must declare with"),
+        }
+    }
+
+    /// Wraps `value` in a call to the `downgrade_with` function, if the type of
+    /// the field changed in `next_version`. Without a `downgrade_with`
+    /// function, the value is wrapped in [`Some`] or unwrapped (returning an
+    /// error if it is not set).
+    fn downgrade_value(&self, value: TokenStream, next_version: &ContainerVersion) -> TokenStream {
+        let Some(change) = self.change_in(next_version) else {
+            return value;
+        };
+
+        if let Some(downgrade_with) = &change.downgrade_with {
+            return quote! { #downgrade_with(#value) };
+        }
+
+        // The field became optional in `next_version` and must thus be set
+        // to be downgraded, or the other way around.
+        match self.option_change(change) {
+            Some(OptionChange::Wrap) => {
+                let message = format!(
+                    "field `{ident}` must be set to downgrade it from version `{version}`",
+                    ident = self.inner.cleaned_ident(),
+                    version = next_version.inner
+                );
+
+                quote! { #value.ok_or(#message)? }
+            }
+            Some(OptionChange::Unwrap) => quote! { ::std::option::Option::Some(#value) },
+            None => unreachable!("internal error: changed field must declare downgrade_with"),
+        }
+    }
+}
+
+/// Describes a type change, which only makes a field optional or required.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OptionChange {
+    /// The field changes from `T` to `Option<T>`.
+    Wrap,
+
+    /// The field changes from `Option<T>` to `T`.
+    Unwrap,
+}
+
+/// Returns whether both types are written the same way. Types are compared
+/// syntactically, which is why `String` and `std::string::String` differ.
+fn same_type(a: &Type, b: &Type) -> bool {
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
 }
@@ -2,18 +2,19 @@ use std::ops::Deref;
 
 use itertools::Itertools;
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{DataStruct, Error, Ident};
+use quote::{format_ident, quote};
+use syn::{DataStruct, Error, GenericArgument, Ident, PathArguments, Type};
 
 use crate::{
     attrs::common::ContainerAttributes,
     codegen::{
         common::{
-            format_container_from_ident, Container, ContainerInput, ContainerVersion, Item,
+            container_from_ident, Container, ContainerInput, ContainerVersion, Item, ItemStatus,
             VersionedContainer,
         },
         vstruct::field::VersionedField,
     },
+    consts::DEPRECATED_FIELD_PREFIX,
 };
 
 pub(crate) mod field;
@@ -43,8 +44,38 @@ impl Container<DataStruct, VersionedField> for VersionedStruct {
             original_attributes,
             visibility,
             ident,
+            generics,
         } = input;
 
+        if attributes.builder.is_present() && !generics.params.is_empty() {
+            return Err(Error::new(
+                attributes.builder.span(),
+                "builders are not supported for generic structs",
+            ));
+        }
+
+        if attributes.round_trip_tests.is_present() && !generics.params.is_empty() {
+            return Err(Error::new(
+                attributes.round_trip_tests.span(),
+                "round-trip tests are not supported for generic structs",
+            ));
+        }
+
+        // The JSON schema is cached in a static, which is shared by all
+        // instantiations of a generic container.
+        if !generics.params.is_empty() {
+            if let Some(k8s) = attributes
+                .k8s
+                .as_ref()
+                .filter(|k8s| k8s.spec_json_schema.is_present())
+            {
+                return Err(Error::new(
+                    k8s.spec_json_schema.span(),
+                    "`spec_json_schema` is not supported for generic containers",
+                ));
+            }
+        }
+
         // Convert the raw version attributes into a container version.
         let versions: Vec<_> = (&attributes).into();
 
@@ -77,19 +108,46 @@ impl Container<DataStruct, VersionedField> for VersionedStruct {
             }
         }
 
-        let from_ident = format_container_from_ident(&ident);
+        // Downgrading a field whose type changed requires a conversion function
+        // in the reverse direction, unless the field only became optional or
+        // required.
+        if attributes.downgrades.is_present() {
+            for item in &items {
+                for change in &item.changes {
+                    if change.downgrade_with.is_none() && item.option_change(change).is_none() {
+                        return Err(Error::new(
+                            change.since.span(),
+                            "field action `changed` requires `downgrade_with` when `downgrades` are generated",
+                        ));
+                    }
+                }
+            }
+        }
+
+        let from_ident = container_from_ident(&ident, attributes.from_name.as_ref())?;
 
         Ok(Self(VersionedContainer {
             skip_from: attributes
                 .options
                 .skip
                 .map_or(false, |s| s.from.is_present()),
+            derive_arbitrary: attributes.derive_arbitrary.is_present(),
+            validate: attributes.validate,
+            k8s: attributes.k8s,
+            version_enum: attributes.version_enum.is_present(),
+            conversions: attributes.conversions,
+            hooks: attributes.hooks,
+            builder: attributes.builder.is_present(),
+            round_trip_tests: attributes.round_trip_tests.is_present(),
+            downgrades: attributes.downgrades.is_present(),
+            upgrade_to_latest: attributes.upgrade_to_latest.is_present(),
             original_attributes,
             visibility,
             from_ident,
             versions,
             items,
             ident,
+            generics,
         }))
     }
 
@@ -101,6 +159,14 @@ impl Container<DataStruct, VersionedField> for VersionedStruct {
             token_stream.extend(self.generate_version(version, versions.peek().copied()));
         }
 
+        token_stream.extend(self.generate_transitive_from_impls());
+        token_stream.extend(self.generate_latest_alias());
+        token_stream.extend(self.generate_version_enum());
+        token_stream.extend(self.generate_any_version_enum());
+        token_stream.extend(self.generate_upgrade_to_latest_fn());
+        token_stream.extend(self.generate_removed_fields_between_fn());
+        token_stream.extend(self.generate_crd_documents_fn());
+
         token_stream
     }
 }
@@ -116,6 +182,8 @@ impl VersionedStruct {
         let original_attributes = &self.original_attributes;
         let visibility = &self.visibility;
         let struct_name = &self.ident;
+        let generics = &self.generics;
+        let where_clause = &self.generics.where_clause;
 
         // Generate fields of the struct for `version`.
         let fields = self.generate_struct_fields(version);
@@ -145,24 +213,56 @@ impl VersionedStruct {
             })
         }
 
+        let arbitrary_attr = self.generate_arbitrary_attr();
+        let api_version_const = self.generate_api_version_const(version);
+        let deprecated_const = self.generate_deprecated_const(version);
+
         // Generate tokens for the module and the contained struct
         token_stream.extend(quote! {
             #[automatically_derived]
             #deprecated_attr
             #visibility mod #version_ident {
+                // Items are declared in the scope of the original container,
+                // which is why their types (and paths) must resolve here too.
+                #[allow(unused_imports)]
+                use super::*;
+
                 #(#original_attributes)*
+                #arbitrary_attr
                 #version_specific_docs
-                pub struct #struct_name {
+                pub struct #struct_name #generics #where_clause {
                     #fields
                 }
+
+                #api_version_const
+                #deprecated_const
             }
         });
 
         // Generate the From impl between this `version` and the next one.
         if !self.skip_from && !version.skip_from {
             token_stream.extend(self.generate_from_impl(version, next_version));
+            token_stream.extend(self.generate_downgrade_impl(version, next_version));
+            token_stream.extend(self.generate_round_trip_helper(version, next_version));
         }
 
+        // Generate the builder for this `version`.
+        if self.builder {
+            token_stream.extend(self.generate_builder(version));
+        }
+
+        // Generate the validate function for this `version`.
+        token_stream.extend(self.generate_validate_impl(version));
+
+        // Generate the matches_version function for this `version`.
+        token_stream.extend(self.generate_matches_version_impl(version));
+
+        // Generate the TryFrom<&DynamicObject> impl for this `version`.
+        token_stream.extend(self.generate_try_from_dynamic_object_impl(version));
+
+        // Generate the spec_json_schema function for this `version`.
+        token_stream.extend(self.generate_spec_json_schema_impl(version));
+
         token_stream
     }
 
@@ -187,19 +287,25 @@ impl VersionedStruct {
 
             let from_ident = &self.from_ident;
             let struct_ident = &self.ident;
+            let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
 
             let fields = self.generate_from_fields(version, next_version, from_ident);
+            let body = self.generate_from_body(quote! {
+                Self {
+                    #fields
+                }
+            });
 
             // TODO (@Techassi): Be a little bit more clever about when to include
             // the #[allow(deprecated)] attribute.
             return quote! {
                 #[automatically_derived]
                 #[allow(deprecated)]
-                impl From<#module_name::#struct_ident> for #next_module_name::#struct_ident {
-                    fn from(#from_ident: #module_name::#struct_ident) -> Self {
-                        Self {
-                            #fields
-                        }
+                impl #impl_generics From<#module_name::#struct_ident #type_generics>
+                    for #next_module_name::#struct_ident #type_generics #where_clause
+                {
+                    fn from(#from_ident: #module_name::#struct_ident #type_generics) -> Self {
+                        #body
                     }
                 }
             };
@@ -222,4 +328,339 @@ impl VersionedStruct {
 
         token_stream
     }
+
+    /// Generates a [`TryFrom`] implementation, which downgrades the struct of
+    /// `next_version` to `version`, if requested via `#[versioned(downgrades)]`.
+    ///
+    /// Fields added in `next_version` have no representation in `version` and
+    /// are dropped. Deprecated fields are still present in later versions and
+    /// are thus moved back. Downgrading a struct therefore only fails if a
+    /// field, which became optional in `next_version`, is not set.
+    fn generate_downgrade_impl(
+        &self,
+        version: &ContainerVersion,
+        next_version: Option<&ContainerVersion>,
+    ) -> Option<TokenStream> {
+        let next_version = next_version.filter(|_| self.downgrades)?;
+
+        let next_module_name = &next_version.ident;
+        let module_name = &version.ident;
+
+        let from_ident = &self.from_ident;
+        let struct_ident = &self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let fields = self
+            .items
+            .iter()
+            .filter_map(|item| item.generate_for_downgrade_impl(version, next_version, from_ident));
+        let body = self.generate_try_from_body(quote! {
+            ::std::result::Result::Ok(Self {
+                #(#fields)*
+            })
+        });
+
+        Some(quote! {
+            #[automatically_derived]
+            #[allow(deprecated)]
+            impl #impl_generics TryFrom<#next_module_name::#struct_ident #type_generics>
+                for #module_name::#struct_ident #type_generics #where_clause
+            {
+                type Error = &'static str;
+
+                fn try_from(
+                    #from_ident: #next_module_name::#struct_ident #type_generics,
+                ) -> ::std::result::Result<Self, Self::Error> {
+                    #body
+                }
+            }
+        })
+    }
+
+    /// Generates the `assert_round_trip` test helper for the struct of
+    /// `version` if requested via `#[versioned(round_trip_tests)]`.
+    ///
+    /// The helper upgrades a clone of the struct to `next_version` and asserts
+    /// that every field present in both versions (with the same type) is
+    /// preserved. If downgrades are generated, the upgraded struct is
+    /// downgraded again, which must restore every field. This catches lossy
+    /// user-supplied conversion functions.
+    fn generate_round_trip_helper(
+        &self,
+        version: &ContainerVersion,
+        next_version: Option<&ContainerVersion>,
+    ) -> Option<TokenStream> {
+        let next_version = next_version.filter(|_| self.round_trip_tests)?;
+
+        let next_module_name = &next_version.ident;
+        let module_name = &version.ident;
+        let struct_ident = &self.ident;
+
+        let mut upgrade_assertions = TokenStream::new();
+        let mut downgrade_assertions = TokenStream::new();
+
+        for item in &self.items {
+            let Some(field_ident) = item.get_ident(version) else {
+                continue;
+            };
+
+            let message = format!(
+                "field `{field_ident}` of `{module_name}::{struct_ident}` is not preserved by converting to `{next_module_name}`"
+            );
+
+            // Fields, whose type changed, can only be compared after the
+            // value has been converted back.
+            if let Some(next_field_ident) = item
+                .get_ident(next_version)
+                .filter(|_| item.change_in(next_version).is_none())
+            {
+                upgrade_assertions.extend(quote! {
+                    ::std::assert_eq!(self.#field_ident, upgraded.#next_field_ident, #message);
+                });
+            }
+
+            downgrade_assertions.extend(quote! {
+                ::std::assert_eq!(self.#field_ident, downgraded.#field_ident, #message);
+            });
+        }
+
+        let downgrade = self.downgrades.then(|| {
+            quote! {
+                let downgraded = <Self as ::std::convert::TryFrom<#next_module_name::#struct_ident>>::try_from(upgraded)
+                    .expect("the upgraded struct must be downgradable");
+                #downgrade_assertions
+            }
+        });
+
+        let doc = format!(
+            "Upgrades a clone of `self` to [`{next_module_name}::{struct_ident}`] and asserts that every field present in both versions is preserved."
+        );
+
+        Some(quote! {
+            #[cfg(test)]
+            #[automatically_derived]
+            #[allow(dead_code, deprecated)]
+            impl #module_name::#struct_ident {
+                #[doc = #doc]
+                ///
+                /// If downgrades are generated, the upgraded struct is
+                /// downgraded again, which must restore every field.
+                pub fn assert_round_trip(&self) {
+                    let upgraded = <#next_module_name::#struct_ident as ::std::convert::From<Self>>::from(
+                        ::std::clone::Clone::clone(self),
+                    );
+                    #upgrade_assertions
+                    #downgrade
+                }
+            }
+        })
+    }
+
+    /// Generates a builder, like `FooV1Builder`, for the struct of `version`
+    /// if requested via `#[versioned(builder)]`. The builder provides a setter
+    /// for every field present in `version`.
+    ///
+    /// Fields of type [`Option`] default to [`None`]. All other fields are
+    /// required, which makes `build` fallible. It then returns the name of the
+    /// first missing field as an error.
+    fn generate_builder(&self, version: &ContainerVersion) -> TokenStream {
+        let builder_ident = format_ident!(
+            "{ident}{variant_ident}Builder",
+            ident = self.ident,
+            variant_ident = version.variant_ident
+        );
+        let struct_ident = &self.ident;
+        let version_ident = &version.ident;
+        let visibility = &self.visibility;
+
+        let mut builder_fields = TokenStream::new();
+        let mut setters = TokenStream::new();
+        let mut build_fields = TokenStream::new();
+        let mut fallible = false;
+
+        for item in &self.items {
+            let Some(field_ident) = item.get_ident(version) else {
+                continue;
+            };
+            let field_ty = item.field_type(version);
+            let field_name = field_ident.to_string();
+            let doc = format!("Sets the `{field_name}` field.");
+
+            match option_inner_type(field_ty) {
+                Some(inner_ty) => {
+                    builder_fields.extend(quote! { #field_ident: #field_ty, });
+                    setters.extend(quote! {
+                        #[doc = #doc]
+                        pub fn #field_ident(mut self, #field_ident: #inner_ty) -> Self {
+                            self.#field_ident = ::std::option::Option::Some(#field_ident);
+                            self
+                        }
+                    });
+                    build_fields.extend(quote! { #field_ident: self.#field_ident, });
+                }
+                None => {
+                    fallible = true;
+                    builder_fields.extend(quote! {
+                        #field_ident: ::std::option::Option<#field_ty>,
+                    });
+                    setters.extend(quote! {
+                        #[doc = #doc]
+                        pub fn #field_ident(mut self, #field_ident: #field_ty) -> Self {
+                            self.#field_ident = ::std::option::Option::Some(#field_ident);
+                            self
+                        }
+                    });
+                    build_fields.extend(quote! {
+                        #field_ident: self.#field_ident.ok_or(#field_name)?,
+                    });
+                }
+            }
+        }
+
+        let build_fn = if fallible {
+            quote! {
+                /// Builds the struct. Returns the name of the first required
+                /// field, which is not set, as an error.
+                pub fn build(
+                    self,
+                ) -> ::std::result::Result<#version_ident::#struct_ident, &'static str> {
+                    ::std::result::Result::Ok(#version_ident::#struct_ident {
+                        #build_fields
+                    })
+                }
+            }
+        } else {
+            quote! {
+                /// Builds the struct.
+                pub fn build(self) -> #version_ident::#struct_ident {
+                    #version_ident::#struct_ident {
+                        #build_fields
+                    }
+                }
+            }
+        };
+
+        let doc = format!("A builder for [`{version_ident}::{struct_ident}`].");
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Default)]
+            #[allow(dead_code)]
+            #visibility struct #builder_ident {
+                #builder_fields
+            }
+
+            #[automatically_derived]
+            #[allow(dead_code, deprecated)]
+            impl #builder_ident {
+                /// Creates a builder without any field set.
+                pub fn new() -> Self {
+                    ::std::default::Default::default()
+                }
+
+                #setters
+
+                #build_fn
+            }
+        }
+    }
+
+    /// Generates the `removed_fields_between` function on the version enum,
+    /// which lists the fields removed between two declared versions. It is
+    /// only generated alongside the version enum.
+    ///
+    /// A field counts as removed if it is present (and not deprecated) in the
+    /// `from` version, but is deprecated or not present at all in the `to`
+    /// version. The field names are returned as declared in the `from`
+    /// version. This also covers downgrades, in which fields added after the
+    /// `to` version are removed.
+    fn generate_removed_fields_between_fn(&self) -> Option<TokenStream> {
+        if !self.version_enum {
+            return None;
+        }
+
+        let version_enum_ident = self.version_enum_ident();
+        let version_strs: Vec<_> = self.versions.iter().map(|v| v.inner.to_string()).collect();
+
+        let arms = self.versions.iter().flat_map(|from| {
+            self.versions.iter().map(move |to| {
+                let from_str = from.inner.to_string();
+                let to_str = to.inner.to_string();
+
+                let removed_fields = self
+                    .items
+                    .iter()
+                    .filter_map(|item| removed_field_name(item, from, to));
+
+                quote! {
+                    (#from_str, #to_str) => ::std::result::Result::Ok(::std::vec![#(#removed_fields),*]),
+                }
+            })
+        });
+
+        Some(quote! {
+            #[automatically_derived]
+            impl #version_enum_ident {
+                /// Returns the names of the fields, which are removed (or
+                /// deprecated) between the `from` and the `to` version. The
+                /// names are returned as declared in the `from` version.
+                ///
+                /// Returns the offending version name, if `from` or `to` is
+                /// not a declared version.
+                pub fn removed_fields_between<'a>(
+                    from: &'a str,
+                    to: &'a str,
+                ) -> ::std::result::Result<::std::vec::Vec<&'static str>, &'a str> {
+                    match (from, to) {
+                        #(#arms)*
+                        _ if ![#(#version_strs),*].contains(&from) => ::std::result::Result::Err(from),
+                        _ => ::std::result::Result::Err(to),
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Returns the name of the `item` in the `from` version, if it is removed
+/// between the `from` and the `to` version.
+fn removed_field_name(
+    item: &VersionedField,
+    from: &ContainerVersion,
+    to: &ContainerVersion,
+) -> Option<String> {
+    let chain = item.chain.as_ref()?;
+
+    // Deprecated fields keep the `deprecated_` prefix in all later versions,
+    // which is why the prefix (instead of the status) marks them as removed.
+    let active_ident = |version: &ContainerVersion| {
+        chain
+            .get(&version.inner)
+            .and_then(ItemStatus::get_ident)
+            .filter(|ident| !ident.to_string().starts_with(DEPRECATED_FIELD_PREFIX))
+    };
+
+    let from_ident = active_ident(from)?;
+    active_ident(to).is_none().then(|| from_ident.to_string())
+}
+
+/// Returns the `T` of a field of type `Option<T>`. Only the last path segment
+/// is checked, which also matches `std::option::Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(inner_ty) => Some(inner_ty),
+            _ => None,
+        },
+        _ => None,
+    }
 }
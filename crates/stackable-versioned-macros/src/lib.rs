@@ -43,14 +43,22 @@ mod consts;
 /// format. The macro checks each declared version and reports any error
 /// encountered during parsing.
 /// The `deprecated` flag marks the version as deprecated. This currently adds
-/// the `#[deprecated]` attribute to the appropriate piece of code.
+/// the `#[deprecated]` attribute to the appropriate piece of code. The
+/// container of a deprecated version and its [`From`] implementation are still
+/// generated. Every version module contains a `DEPRECATED` constant, which
+/// enables reconcilers to warn about objects stored in a deprecated version.
 ///
 /// ```
 /// # use stackable_versioned_macros::versioned;
 /// #[versioned(
-///     version(name = "v1alpha1", deprecated)
+///     version(name = "v1alpha1", deprecated),
+///     version(name = "v1")
 /// )]
 /// struct Foo {}
+///
+/// #[allow(deprecated)]
+/// assert!(v1alpha1::DEPRECATED);
+/// assert!(!v1::DEPRECATED);
 /// ```
 ///
 /// Additionally, it is ensured that each version is unique. Declaring the same
@@ -70,9 +78,9 @@ mod consts;
 ///
 /// ### Field Actions
 ///
-/// This library currently supports three different field actions. Fields can
-/// be added, renamed and deprecated. The macro ensures that these actions
-/// adhere to the following set of rules:
+/// This library currently supports four different field actions. Fields can
+/// be added, renamed, deprecated and their type can be changed. The macro
+/// ensures that these actions adhere to the following set of rules:
 ///
 /// - Fields cannot be added and deprecated in the same version.
 /// - Fields cannot be added and renamed in the same version.
@@ -83,12 +91,66 @@ mod consts;
 ///   b<sub>n</sub> < c_.
 /// - All field actions must use previously declared versions. Using versions
 ///   not present at the container level will result in an error.
+/// - The type of a field can only be changed after the field was added and at
+///   most once per version.
 ///
 /// For fields marked as deprecated, two additional rules apply:
 ///
 /// - Fields must start with the `deprecated_` prefix.
 /// - The deprecation note cannot be empty.
 ///
+/// ### Forwarded Attributes
+///
+/// Attributes of the container and its items (like doc-comments or `serde`
+/// attributes) are forwarded onto the generated container and items of every
+/// version. As an exception, a `#[serde(rename)]` refers to the latest name of
+/// an item and is thus not forwarded to versions in which the item is named
+/// differently (because it is renamed later on).
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1")
+/// )]
+/// #[derive(serde::Serialize)]
+/// #[serde(rename_all = "camelCase")]
+/// pub struct Foo {
+///     #[versioned(renamed(since = "v1", from = "bucket"))]
+///     #[serde(rename = "name")]
+///     bucket_name: String,
+/// }
+///
+/// let foo = v1alpha1::Foo { bucket: "a".to_owned() };
+/// assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"bucket":"a"}"#);
+///
+/// let foo = v1::Foo { bucket_name: "a".to_owned() };
+/// assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"name":"a"}"#);
+/// ```
+///
+/// ### Generics
+///
+/// Generic parameters (including lifetimes and the where clause) of the
+/// container are forwarded onto the container of every version and onto all
+/// generated implementations. Every version must use all generic parameters.
+/// Builders and `k8s(spec_json_schema)` are not supported for generic
+/// containers.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1")
+/// )]
+/// pub struct Foo<T> {
+///     #[versioned(renamed(since = "v1", from = "bar"))]
+///     inner: T,
+/// }
+///
+/// let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+/// assert_eq!(foo.inner, 42);
+/// ```
+///
 /// ### Auto-generated [`From`] Implementations
 ///
 /// To enable smooth version upgrades of the same struct, the macro automatically
@@ -155,6 +217,151 @@ mod consts;
 /// }
 /// ```
 ///
+/// The source object is named `__sv_<container>` in the generated [`From`]
+/// implementations. If this name collides with an existing item or doesn't
+/// match the naming conventions of a crate, it can be overridden using the
+/// `from_name` option:
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     from_name = "source"
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// // Produces (amongst other things) ...
+/// //
+/// // impl From<v1alpha1::Foo> for v1::Foo {
+/// //     fn from(source: v1alpha1::Foo) -> Self {
+/// //         Self { bar: source.bar }
+/// //     }
+/// // }
+///
+/// let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+/// assert_eq!(foo.bar, 42);
+/// ```
+///
+/// #### Variants carrying Data
+///
+/// Enums can contain tuple and struct variants. The generated [`From`]
+/// implementations convert the data of these variants using [`Into`], which
+/// enables the data to be versioned as well. Variants added in the newer
+/// version simply have no source in the older version.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1")
+/// )]
+/// pub enum Foo {
+///     #[versioned(renamed(since = "v1", from = "Baz"))]
+///     Bar { port: u16 },
+///     #[versioned(added(since = "v1"))]
+///     Qux(String),
+/// }
+///
+/// let foo = v1::Foo::from(v1alpha1::Foo::Baz { port: 8080 });
+/// assert!(matches!(foo, v1::Foo::Bar { port: 8080 }));
+/// ```
+///
+/// #### Conversions across multiple versions
+///
+/// By default, [`From`] implementations are only generated between adjacent
+/// versions, which keeps the generated code minimal. Converting across multiple
+/// versions then requires multiple `into` calls. Using the `conversions`
+/// option, additional implementations can be generated, which trades compile
+/// time and output size for convenience:
+///
+/// - `conversions = "adjacent"` (default) generates implementations between
+///   adjacent versions only.
+/// - `conversions = "to_latest"` additionally generates implementations from
+///   every version to the latest version.
+/// - `conversions = "all_pairs"` generates implementations from every version
+///   to every later version.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1"),
+///     version(name = "v1"),
+///     conversions = "to_latest"
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+/// assert_eq!(foo.bar, 42);
+/// ```
+///
+/// Implementations spanning multiple versions chain the implementations of the
+/// adjacent versions. They are not generated if the generation of [`From`]
+/// implementations is skipped for any version in between.
+///
+/// #### Downgrades
+///
+/// Writing back an older stored representation requires converting newer
+/// versions to older ones. Adding the `downgrades` flag generates [`TryFrom`]
+/// implementations from every version to the previous (adjacent) version. Fields
+/// added in the newer version have no representation in the older version and
+/// are dropped. Variants added in the newer version can't be downgraded at all,
+/// which is why their name is returned as an error. Downgrades are skipped
+/// together with the [`From`] implementation of the same pair of versions.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     downgrades
+/// )]
+/// pub struct Foo {
+///     #[versioned(added(since = "v1"))]
+///     bar: usize,
+///     baz: bool,
+/// }
+///
+/// let foo = v1alpha1::Foo::try_from(v1::Foo { bar: 42, baz: true }).unwrap();
+/// assert!(foo.baz);
+/// ```
+///
+/// #### Round-trip Tests
+///
+/// Adding the `round_trip_tests` flag to structs generates the
+/// `assert_round_trip` test helper (behind `#[cfg(test)]`) for every version
+/// with a [`From`] implementation. It upgrades a clone of a sample to the next
+/// version and asserts that every field present in both versions is preserved.
+/// If `downgrades` are generated, the upgraded value is downgraded again,
+/// which must restore every field. This catches lossy conversion functions.
+/// The struct must implement [`Clone`] and all fields [`PartialEq`] and
+/// [`Debug`].
+///
+/// ```ignore
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     downgrades,
+///     round_trip_tests
+/// )]
+/// #[derive(Clone, Debug, PartialEq)]
+/// pub struct Foo {
+///     #[versioned(added(since = "v1"))]
+///     bar: usize,
+///     baz: bool,
+/// }
+///
+/// #[test]
+/// fn round_trip() {
+///     v1alpha1::Foo { baz: true }.assert_round_trip();
+/// }
+/// ```
+///
 /// #### Skip [`From`] generation
 ///
 /// Generation of these [`From`] implementations can be skipped at the container
@@ -179,6 +386,26 @@ mod consts;
 /// }
 /// ```
 ///
+/// Skipping at the version level only omits the implementation from that
+/// version to the next one, like `From<v1alpha1::Foo> for v1beta1::Foo` below.
+/// As the last version has no next version, it can't skip anything, which is
+/// why `skip(from)` is rejected there.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1", skip(from)),
+///     version(name = "v1beta1"),
+///     version(name = "v1")
+/// )]
+/// pub struct Foo {
+///     baz: bool,
+/// }
+///
+/// let foo = v1::Foo::from(v1beta1::Foo { baz: true });
+/// assert!(foo.baz);
+/// ```
+///
 /// #### Customize Default Function for Added Fields
 ///
 /// It is possible to customize the default function used in the generated
@@ -205,6 +432,459 @@ mod consts;
 ///     42
 /// }
 /// ```
+///
+/// #### Changed Field Types
+///
+/// If the type of a field changes between versions, the `changed` action
+/// declares the previous type via `from_type` and the function converting the
+/// previous value via `with`. The generated [`From`] implementation calls this
+/// function instead of moving the value. If `downgrades` are generated, the
+/// function converting the value back has to be declared via `downgrade_with`.
+///
+/// ```
+/// # use std::net::IpAddr;
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1")
+/// )]
+/// pub struct Foo {
+///     #[versioned(changed(since = "v1beta1", from_type = "String", with = "parse_addr"))]
+///     addr: std::net::IpAddr,
+/// }
+///
+/// fn parse_addr(addr: String) -> IpAddr {
+///     addr.parse().unwrap()
+/// }
+///
+/// let foo = v1beta1::Foo::from(v1alpha1::Foo { addr: "127.0.0.1".to_owned() });
+/// assert!(foo.addr.is_loopback());
+/// ```
+///
+/// If a field only becomes optional (`T` to `Option<T>`), `with` and
+/// `downgrade_with` can be omitted. The value is wrapped in [`Some`] when
+/// upgrading. Downgrading fails if the value is [`None`]. If a field becomes
+/// required (`Option<T>` to `T`), the value is unwrapped when upgrading, which
+/// panics if it is [`None`]. Declare `with` to handle this case gracefully.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1")
+/// )]
+/// pub struct Foo {
+///     #[versioned(changed(since = "v1beta1", from_type = "usize"))]
+///     bar: Option<usize>,
+/// }
+///
+/// let foo = v1beta1::Foo::from(v1alpha1::Foo { bar: 42 });
+/// assert_eq!(foo.bar, Some(42));
+/// ```
+///
+/// #### Conversion Hooks
+///
+/// Cross-cutting concerns, like metrics or validation, can be implemented
+/// around the generated [`From`] (and [`TryFrom`], see `downgrades`)
+/// implementations using hooks. The `pre_convert` function is called with a
+/// reference to the source object before the conversion, the `post_convert`
+/// function is called with a reference to the produced object after a
+/// successful conversion. Both hooks are optional and must accept the
+/// containers of all versions (usually by being generic). Conversions across
+/// multiple versions call the hooks once per adjacent pair of versions.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     hooks(pre_convert = "log_source", post_convert = "log_produced")
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// fn log_source<T>(_source: &T) {
+///     println!("converting {}", std::any::type_name::<T>());
+/// }
+///
+/// fn log_produced<T>(_produced: &T) {
+///     println!("converted to {}", std::any::type_name::<T>());
+/// }
+///
+/// let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+/// assert_eq!(foo.bar, 42);
+/// ```
+///
+/// #### Passthrough Fields
+///
+/// Fields which preserve unknown data (like maps using the
+/// `x-kubernetes-preserve-unknown-fields` schema extension) can be marked as
+/// `passthrough`. The marker is only a lint: To prevent silently dropping the
+/// preserved data, passthrough fields cannot be deprecated or changed, which
+/// guarantees that the generated [`From`] implementations copy them verbatim
+/// between versions, including renamed ones.
+///
+/// The marker doesn't generate any code. Especially, it doesn't add the
+/// `x-kubernetes-preserve-unknown-fields` schema extension, which still needs
+/// to be set on the field, e.g. using `#[schemars(schema_with = "...")]`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1")
+/// )]
+/// pub struct Foo {
+///     #[versioned(passthrough, renamed(since = "v1", from = "extra"))]
+///     overrides: std::collections::BTreeMap<String, String>,
+///     baz: bool,
+/// }
+/// ```
+///
+/// ### Validation
+///
+/// A validation function can be declared via `validate(with = "...", error =
+/// "...")`. Every version of the container then gains a `validate` function,
+/// which calls the declared function with a reference to itself. As the same
+/// function is used for every version, it usually is generic over the
+/// containers.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     validate(with = "validate_foo", error = "String")
+/// )]
+/// #[derive(Debug)]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// fn validate_foo<T: std::fmt::Debug>(foo: &T) -> Result<(), String> {
+///     // Check invariants of any version of Foo ...
+///     Ok(())
+/// }
+///
+/// assert!(v1::Foo { bar: 42 }.validate().is_ok());
+/// ```
+///
+/// ### Arbitrary Inputs
+///
+/// To fuzz or property-test the conversions between versions, the macro can
+/// derive [`arbitrary::Arbitrary`][1] for every version of the container by
+/// adding the `derive_arbitrary` flag. This requires the `arbitrary` crate
+/// (with the `derive` feature enabled) as a dependency, which is why it is
+/// usually only enabled in test or fuzzing builds.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     derive_arbitrary
+/// )]
+/// pub struct Foo {
+///     #[versioned(added(since = "v1"))]
+///     bar: usize,
+///     baz: bool,
+/// }
+/// ```
+///
+/// [1]: https://docs.rs/arbitrary/latest/arbitrary/trait.Arbitrary.html
+///
+/// ### Kubernetes Specific Options
+///
+/// Kubernetes specific details of the container can be declared via `k8s()`.
+/// Currently, the API `group` can be declared, which is used in the
+/// generated `matches_version` function. This function is generated for every
+/// version and reports whether an `apiVersion` string matches the group and
+/// version of the container. Without a declared group, only the version is
+/// compared.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     k8s(group = "stackable.tech")
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// assert!(v1::Foo::matches_version("stackable.tech/v1"));
+/// assert!(!v1alpha1::Foo::matches_version("stackable.tech/v1"));
+/// ```
+///
+/// With a declared group, every version module additionally contains an
+/// `API_VERSION` constant, like `stackable.tech/v1`. It can be used to build
+/// owner references or patches without hardcoding the `apiVersion`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     k8s(group = "stackable.tech")
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// assert_eq!(v1alpha1::API_VERSION, "stackable.tech/v1alpha1");
+/// assert_eq!(v1::API_VERSION, "stackable.tech/v1");
+/// ```
+///
+/// Adding `try_from_dynamic_object` generates a `TryFrom<&DynamicObject>`
+/// implementation for every version, which is useful for webhooks receiving
+/// objects of unknown versions. The `spec` of the object is only deserialized
+/// if the `apiVersion` of the object matches the version, otherwise an error is
+/// returned. The container must implement `Deserialize` and the crate must
+/// depend on `kube`, `serde` and `serde_json`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// # use kube::api::{ApiResource, DynamicObject, GroupVersionKind};
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     k8s(group = "stackable.tech", try_from_dynamic_object)
+/// )]
+/// #[derive(serde::Deserialize)]
+/// pub struct FooSpec {
+///     bar: usize,
+/// }
+///
+/// let gvk = GroupVersionKind::gvk("stackable.tech", "v1", "Foo");
+/// let object = DynamicObject::new("foo", &ApiResource::from_gvk(&gvk))
+///     .data(serde_json::json!({ "spec": { "bar": 42 } }));
+///
+/// assert_eq!(v1::FooSpec::try_from(&object).unwrap().bar, 42);
+/// assert!(v1alpha1::FooSpec::try_from(&object).is_err());
+/// ```
+///
+/// Adding `spec_json_schema` generates a `spec_json_schema` function for every
+/// version, which returns the bare JSON schema of the container (instead of the
+/// whole CRD), e.g. to validate objects in admission webhooks. The schema is
+/// only computed once. The container must implement `JsonSchema` and the crate
+/// must depend on `schemars` and `serde_json`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     k8s(group = "stackable.tech", spec_json_schema)
+/// )]
+/// #[derive(schemars::JsonSchema)]
+/// pub struct FooSpec {
+///     bar: usize,
+/// }
+///
+/// let schema = v1::FooSpec::spec_json_schema();
+/// assert!(schema["properties"]["bar"].is_object());
+/// ```
+///
+/// Adding `crd_documents` (which requires the `kind` of the custom resource
+/// derived via `#[derive(CustomResource)]`) generates a function, like
+/// `foo_spec_crd_documents`, which returns the standalone CRD of every version
+/// as a separate YAML document. Contrary to a merged multi-version CRD, these
+/// documents can be composed externally, e.g. using Kustomize. The version
+/// declared in `#[kube(version)]` is replaced by the version of the module. The
+/// crate must depend on `kube` and `serde_yaml`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     k8s(group = "stackable.tech", kind = "Foo", crd_documents)
+/// )]
+/// #[derive(
+///     Clone, Debug, kube::CustomResource, schemars::JsonSchema, serde::Deserialize, serde::Serialize,
+/// )]
+/// #[kube(group = "stackable.tech", version = "v1", kind = "Foo")]
+/// pub struct FooSpec {
+///     bar: usize,
+/// }
+///
+/// let documents = foo_spec_crd_documents();
+/// assert_eq!(documents.len(), 2);
+/// ```
+///
+/// ### Version Enum
+///
+/// Adding the `version_enum` flag generates an enum, like `FooVersion`, with
+/// one variant per declared version. The variants are ordered by the
+/// Kubernetes version precedence, which enables comparisons like
+/// `stored_version < latest_version`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1"),
+///     version(name = "v1"),
+///     version_enum
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// // Produces (amongst other things) ...
+/// // pub enum FooVersion { V1Alpha1, V1Beta1, V1 }
+///
+/// assert!(FooVersion::V1Alpha1 < FooVersion::V1Beta1);
+/// assert!(FooVersion::V1Beta1 < FooVersion::V1);
+///
+/// // The version enum can be converted from and to the version string
+/// assert_eq!(FooVersion::V1Beta1.as_str(), "v1beta1");
+/// assert_eq!("v1".parse(), Ok(FooVersion::V1));
+/// ```
+///
+/// Unless the generation of `From` implementations is skipped for the whole
+/// container, an enum like `FooAnyVersion` is generated as well. It holds the
+/// container of any declared version and upgrades it to the latest version via
+/// `try_into_latest`, which additionally returns the versions traversed. This
+/// helps debugging multi-hop conversions. As the value is upgraded in the order
+/// of declaration, the latest version must be declared last, even if
+/// `options(allow_unsorted)` is set.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1"),
+///     version(name = "v1"),
+///     version_enum
+/// )]
+/// pub struct Foo {
+///     bar: usize,
+/// }
+///
+/// let foo = FooAnyVersion::V1Alpha1(v1alpha1::Foo { bar: 42 });
+/// let (foo, path) = foo.try_into_latest().unwrap();
+///
+/// assert_eq!(foo.bar, 42);
+/// assert_eq!(path, ["v1alpha1", "v1beta1", "v1"]);
+/// ```
+///
+/// Adding the `upgrade_to_latest` flag (which requires the `version_enum`
+/// flag) additionally generates `FooVersion::upgrade_to_latest`. It is useful
+/// for untyped objects with a known `apiVersion`: the value is deserialized
+/// into the container of the given version and then upgraded to the latest
+/// version. Unknown versions and values not matching the version are returned
+/// as a `serde_json::Error`. The containers of all versions must implement
+/// `serde::Deserialize`.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     version_enum,
+///     upgrade_to_latest
+/// )]
+/// #[derive(serde::Deserialize)]
+/// pub struct Foo {
+///     #[versioned(renamed(since = "v1", from = "baz"))]
+///     bar: usize,
+/// }
+///
+/// let value = serde_json::json!({ "baz": 42 });
+/// let foo = FooVersion::upgrade_to_latest("v1alpha1", value).unwrap();
+/// assert_eq!(foo.bar, 42);
+///
+/// let value = serde_json::json!({ "bar": 42 });
+/// assert!(FooVersion::upgrade_to_latest("v2", value).is_err());
+/// ```
+///
+/// For structs, the version enum additionally provides
+/// `removed_fields_between`, which lists the fields removed (deprecated or not
+/// present at all) between two declared versions. This is useful for
+/// documentation and migration tooling. Unknown version names are returned as
+/// an error.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1beta1"),
+///     version(name = "v1"),
+///     version_enum
+/// )]
+/// pub struct Foo {
+///     #[versioned(deprecated(since = "v1beta1", note = "not needed"))]
+///     deprecated_bar: usize,
+///     baz: bool,
+/// }
+///
+/// assert_eq!(
+///     FooVersion::removed_fields_between("v1alpha1", "v1"),
+///     Ok(vec!["bar"])
+/// );
+/// assert_eq!(FooVersion::removed_fields_between("v1alpha1", "v2"), Err("v2"));
+/// ```
+///
+/// ### Builders
+///
+/// Adding the `builder` flag to structs generates a builder, like
+/// `FooV1Builder`, for every version. The builder provides a setter for every
+/// field present in that version. Fields of type [`Option`] default to
+/// [`None`], all other fields are required. If a version contains required
+/// fields, `build` returns the name of the first missing field as an error.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1"),
+///     builder
+/// )]
+/// pub struct Foo {
+///     #[versioned(added(since = "v1"))]
+///     bar: usize,
+///     baz: Option<bool>,
+/// }
+///
+/// let foo = FooV1Builder::new().bar(42).build().unwrap();
+/// assert_eq!(foo.bar, 42);
+/// assert_eq!(foo.baz, None);
+///
+/// assert_eq!(FooV1Builder::new().baz(true).build().err(), Some("bar"));
+///
+/// // v1alpha1 has no required fields, which is why building can't fail
+/// let foo = FooV1Alpha1Builder::new().build();
+/// assert_eq!(foo.baz, None);
+/// ```
+///
+/// ### Latest Type Alias
+///
+/// The macro additionally generates a type alias, which always points to the
+/// container definition of the latest declared version. The alias uses the
+/// container name followed by the `Latest` suffix and is generated with the
+/// visibility of the container. Declaring a new (newer) version automatically
+/// retargets the alias.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "v1alpha1"),
+///     version(name = "v1")
+/// )]
+/// pub struct Foo {
+///     #[versioned(added(since = "v1"))]
+///     bar: usize,
+///     baz: bool,
+/// }
+///
+/// // Produces (amongst other things) ...
+/// // pub type FooLatest = v1::Foo;
+///
+/// let foo: FooLatest = v1::Foo { bar: 42, baz: true };
+/// ```
 #[proc_macro_attribute]
 pub fn versioned(attrs: TokenStream, input: TokenStream) -> TokenStream {
     let attrs = match NestedMeta::parse_meta_list(attrs.into()) {
@@ -0,0 +1,84 @@
+use arbitrary::{Arbitrary, Unstructured};
+use stackable_versioned_macros::versioned;
+
+/// The amount of random inputs each conversion is tested with.
+const ITERATIONS: usize = 1000;
+
+/// Returns pseudo-random bytes, which are used as the input of [`Unstructured`].
+///
+/// A simple xorshift generator with a fixed seed is used, so that the test is
+/// deterministic and doesn't require an additional dependency.
+fn random_bytes(seed: &mut u64, len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            (*seed >> 24) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn arbitrary_struct_conversion() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        derive_arbitrary
+    )]
+    #[derive(Clone, Debug)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"), renamed(since = "v1", from = "bat"))]
+        bar: usize,
+
+        #[versioned(renamed(since = "v1", from = "qux"))]
+        baz: Option<String>,
+
+        items: Vec<u16>,
+    }
+
+    let mut seed = 0x5eed_u64;
+
+    for _ in 0..ITERATIONS {
+        let bytes = random_bytes(&mut seed, 64);
+        let mut input = Unstructured::new(&bytes);
+        let foo_v1alpha1 = v1alpha1::Foo::arbitrary(&mut input).expect("enough input data");
+
+        let foo_v1beta1 = v1beta1::Foo::from(foo_v1alpha1.clone());
+        assert_eq!(foo_v1beta1.bat, 0);
+        assert_eq!(foo_v1beta1.qux, foo_v1alpha1.qux);
+        assert_eq!(foo_v1beta1.items, foo_v1alpha1.items);
+
+        let foo_v1 = v1::Foo::from(foo_v1beta1.clone());
+        assert_eq!(foo_v1.bar, foo_v1beta1.bat);
+        assert_eq!(foo_v1.baz, foo_v1beta1.qux);
+        assert_eq!(foo_v1.items, foo_v1beta1.items);
+    }
+}
+
+#[test]
+fn arbitrary_enum_conversion() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), derive_arbitrary)]
+    #[derive(Clone, Debug)]
+    pub enum Mode {
+        #[versioned(renamed(since = "v1", from = "Basic"))]
+        Simple,
+        Advanced,
+    }
+
+    let mut seed = 0x5eed_u64;
+
+    for _ in 0..ITERATIONS {
+        let bytes = random_bytes(&mut seed, 8);
+        let mut input = Unstructured::new(&bytes);
+        let mode_v1alpha1 = v1alpha1::Mode::arbitrary(&mut input).expect("enough input data");
+
+        let mode_v1 = v1::Mode::from(mode_v1alpha1.clone());
+        match (mode_v1alpha1, mode_v1) {
+            (v1alpha1::Mode::Basic, v1::Mode::Simple) => {}
+            (v1alpha1::Mode::Advanced, v1::Mode::Advanced) => {}
+            (old, new) => panic!("{old:?} was converted to {new:?}"),
+        }
+    }
+}
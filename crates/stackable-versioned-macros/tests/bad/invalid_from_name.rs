@@ -0,0 +1,12 @@
+use stackable_versioned_macros::versioned;
+
+fn main() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        from_name = "not valid"
+    )]
+    struct Foo {
+        bar: usize,
+    }
+}
@@ -0,0 +1,11 @@
+use stackable_versioned_macros::versioned;
+
+fn main() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1", skip(from))
+    )]
+    struct Foo {
+        bar: usize,
+    }
+}
@@ -0,0 +1,11 @@
+use stackable_versioned_macros::versioned;
+
+fn main() {
+    #[versioned(
+        version(name = "v1"),
+        version(name = "v1alpha1")
+    )]
+    struct Foo {
+        bar: usize,
+    }
+}
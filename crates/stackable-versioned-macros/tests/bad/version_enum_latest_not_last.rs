@@ -0,0 +1,13 @@
+use stackable_versioned_macros::versioned;
+
+fn main() {
+    #[versioned(
+        version(name = "v1"),
+        version(name = "v1alpha1"),
+        options(allow_unsorted),
+        version_enum
+    )]
+    struct Foo {
+        bar: usize,
+    }
+}
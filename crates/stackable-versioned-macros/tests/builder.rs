@@ -0,0 +1,79 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1beta1"),
+    version(name = "v1"),
+    builder
+)]
+#[allow(dead_code)]
+pub struct Foo {
+    #[versioned(added(since = "v1beta1"))]
+    bar: usize,
+
+    #[versioned(deprecated(since = "v1", note = "not needed"))]
+    deprecated_baz: bool,
+
+    qux: Option<String>,
+}
+
+#[test]
+fn builder_all_fields() {
+    let foo = FooV1Beta1Builder::new()
+        .bar(42)
+        .baz(true)
+        .qux("qux".to_owned())
+        .build()
+        .expect("all required fields are set");
+
+    assert_eq!(foo.bar, 42);
+    assert!(foo.baz);
+    assert_eq!(foo.qux.as_deref(), Some("qux"));
+}
+
+#[test]
+fn builder_optional_field() {
+    // Fields added in later versions are not part of earlier builders
+    let foo = FooV1Alpha1Builder::new()
+        .baz(false)
+        .build()
+        .expect("all required fields are set");
+
+    assert!(!foo.baz);
+    assert_eq!(foo.qux, None);
+}
+
+#[test]
+#[allow(deprecated)]
+fn builder_deprecated_field() {
+    let foo = FooV1Builder::new()
+        .bar(1)
+        .deprecated_baz(true)
+        .build()
+        .expect("all required fields are set");
+
+    assert_eq!(foo.bar, 1);
+    assert!(foo.deprecated_baz);
+}
+
+#[test]
+fn builder_missing_required_field() {
+    let result = FooV1Beta1Builder::new().baz(true).build();
+    assert!(matches!(result, Err("bar")));
+
+    let result = FooV1Beta1Builder::new().build();
+    assert!(matches!(result, Err("bar")));
+}
+
+#[test]
+fn builder_infallible() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), builder)]
+    pub struct Bar {
+        baz: Option<u16>,
+    }
+
+    // Without any required field, building can't fail
+    let bar: v1::Bar = BarV1Builder::new().baz(8080).build();
+    assert_eq!(bar.baz, Some(8080));
+    assert_eq!(BarV1Alpha1Builder::new().build().baz, None);
+}
@@ -0,0 +1,111 @@
+use std::net::IpAddr;
+
+use stackable_versioned_macros::versioned;
+
+fn parse_addr(addr: String) -> IpAddr {
+    addr.parse().expect("test addresses must be valid")
+}
+
+fn format_addr(addr: IpAddr) -> String {
+    addr.to_string()
+}
+
+#[test]
+fn changed() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    pub struct Foo {
+        #[versioned(changed(since = "v1beta1", from_type = "String", with = "parse_addr"))]
+        addr: IpAddr,
+        baz: bool,
+    }
+
+    let foo = v1alpha1::Foo {
+        addr: "127.0.0.1".to_owned(),
+        baz: true,
+    };
+
+    let foo = v1beta1::Foo::from(foo);
+    assert_eq!(foo.addr, IpAddr::from([127, 0, 0, 1]));
+
+    let foo = v1::Foo::from(foo);
+    assert_eq!(foo.addr, IpAddr::from([127, 0, 0, 1]));
+    assert!(foo.baz);
+}
+
+#[test]
+fn changed_renamed() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), downgrades)]
+    pub struct Foo {
+        #[versioned(
+            renamed(since = "v1", from = "host"),
+            changed(
+                since = "v1",
+                from_type = "String",
+                with = "parse_addr",
+                downgrade_with = "format_addr"
+            )
+        )]
+        addr: IpAddr,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo {
+        host: "::1".to_owned(),
+    });
+    assert_eq!(foo.addr, "::1".parse::<IpAddr>().unwrap());
+
+    let foo = v1alpha1::Foo::try_from(foo).expect("structs can always be downgraded");
+    assert_eq!(foo.host, "::1");
+}
+
+#[test]
+fn changed_to_optional() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), downgrades)]
+    pub struct Foo {
+        #[versioned(changed(since = "v1", from_type = "usize"))]
+        bar: Option<usize>,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+    assert_eq!(foo.bar, Some(42));
+
+    let foo = v1alpha1::Foo::try_from(foo).expect("set fields can be downgraded");
+    assert_eq!(foo.bar, 42);
+
+    let error = v1alpha1::Foo::try_from(v1::Foo { bar: None })
+        .expect_err("unset fields cannot be downgraded");
+    assert_eq!(
+        error,
+        "field `bar` must be set to downgrade it from version `v1`"
+    );
+}
+
+#[test]
+fn changed_to_required() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), downgrades)]
+    pub struct Foo {
+        #[versioned(changed(since = "v1", from_type = "Option<usize>"))]
+        bar: usize,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo { bar: Some(42) });
+    assert_eq!(foo.bar, 42);
+
+    let foo = v1alpha1::Foo::try_from(foo).expect("structs can always be downgraded");
+    assert_eq!(foo.bar, Some(42));
+}
+
+#[test]
+#[should_panic(expected = "field `bar` must be set to convert it to version `v1`")]
+fn changed_to_required_unset() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    pub struct Foo {
+        #[versioned(changed(since = "v1", from_type = "Option<usize>"))]
+        bar: usize,
+    }
+
+    let _ = v1::Foo::from(v1alpha1::Foo { bar: None });
+}
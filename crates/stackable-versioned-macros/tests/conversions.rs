@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+
+use stackable_versioned_macros::versioned;
+
+/// Used by [`implements_from`] to check whether `T` implements `From<F>`,
+/// without failing to compile if it doesn't. This uses the fact that inherent
+/// associated items take precedence over trait associated items, but only if
+/// their bounds are met.
+#[allow(dead_code)]
+struct Probe<F, T>(PhantomData<(F, T)>);
+
+trait NoFrom {
+    const IMPLEMENTS_FROM: bool = false;
+}
+
+impl<F, T> NoFrom for Probe<F, T> {}
+
+#[allow(dead_code)]
+impl<F, T: From<F>> Probe<F, T> {
+    const IMPLEMENTS_FROM: bool = true;
+}
+
+macro_rules! implements_from {
+    ($from:ty => $to:ty) => {
+        <Probe<$from, $to>>::IMPLEMENTS_FROM
+    };
+}
+
+#[test]
+fn conversions_adjacent() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version(name = "v2")
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    assert!(implements_from!(v1alpha1::Foo => v1beta1::Foo));
+    assert!(implements_from!(v1beta1::Foo => v1::Foo));
+    assert!(implements_from!(v1::Foo => v2::Foo));
+
+    assert!(!implements_from!(v1alpha1::Foo => v1::Foo));
+    assert!(!implements_from!(v1alpha1::Foo => v2::Foo));
+    assert!(!implements_from!(v1beta1::Foo => v2::Foo));
+
+    // Conversions are never generated from newer to older versions
+    assert!(!implements_from!(v1beta1::Foo => v1alpha1::Foo));
+}
+
+#[test]
+fn conversions_to_latest() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version(name = "v2"),
+        conversions = "to_latest"
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1", default = "default_bar"))]
+        bar: usize,
+        baz: bool,
+    }
+
+    fn default_bar() -> usize {
+        42
+    }
+
+    assert!(implements_from!(v1alpha1::Foo => v1beta1::Foo));
+    assert!(implements_from!(v1beta1::Foo => v1::Foo));
+    assert!(implements_from!(v1::Foo => v2::Foo));
+    assert!(implements_from!(v1alpha1::Foo => v2::Foo));
+    assert!(implements_from!(v1beta1::Foo => v2::Foo));
+
+    assert!(!implements_from!(v1alpha1::Foo => v1::Foo));
+    assert!(!implements_from!(v2::Foo => v1alpha1::Foo));
+
+    let foo = v2::Foo::from(v1alpha1::Foo { baz: true });
+    assert_eq!(foo.bar, 42);
+    assert!(foo.baz);
+}
+
+#[test]
+fn conversions_all_pairs() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version(name = "v2"),
+        conversions = "all_pairs"
+    )]
+    #[allow(dead_code)]
+    pub enum Foo {
+        Bar,
+        #[versioned(renamed(since = "v1", from = "Qux"))]
+        Baz,
+    }
+
+    assert!(implements_from!(v1alpha1::Foo => v1beta1::Foo));
+    assert!(implements_from!(v1alpha1::Foo => v1::Foo));
+    assert!(implements_from!(v1alpha1::Foo => v2::Foo));
+    assert!(implements_from!(v1beta1::Foo => v1::Foo));
+    assert!(implements_from!(v1beta1::Foo => v2::Foo));
+    assert!(implements_from!(v1::Foo => v2::Foo));
+
+    assert!(!implements_from!(v1::Foo => v1alpha1::Foo));
+
+    assert!(matches!(v1::Foo::from(v1alpha1::Foo::Qux), v1::Foo::Baz));
+}
+
+#[test]
+fn conversions_skip_from_version() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1", skip(from)),
+        version(name = "v1"),
+        version(name = "v2"),
+        conversions = "all_pairs"
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    impl From<v1beta1::Foo> for v1::Foo {
+        fn from(foo: v1beta1::Foo) -> Self {
+            Self { bar: foo.bar * 2 }
+        }
+    }
+
+    // Conversions spanning the skipped version are not generated
+    assert!(!implements_from!(v1alpha1::Foo => v2::Foo));
+    assert!(!implements_from!(v1beta1::Foo => v2::Foo));
+
+    assert!(implements_from!(v1alpha1::Foo => v1beta1::Foo));
+    assert!(implements_from!(v1::Foo => v2::Foo));
+}
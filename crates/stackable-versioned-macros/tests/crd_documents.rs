@@ -0,0 +1,54 @@
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1"),
+    k8s(group = "stackable.tech", kind = "Foo", crd_documents)
+)]
+#[derive(
+    Clone, Debug, kube::CustomResource, schemars::JsonSchema, serde::Deserialize, serde::Serialize,
+)]
+#[kube(group = "stackable.tech", version = "v1", kind = "Foo", namespaced)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub struct FooSpec {
+    bar: usize,
+
+    #[versioned(added(since = "v1"))]
+    baz: bool,
+}
+
+#[test]
+fn crd_documents_per_version() {
+    let documents = foo_spec_crd_documents();
+    assert_eq!(documents.len(), 2);
+
+    let crds = documents
+        .iter()
+        .map(|document| {
+            serde_yaml::from_str::<CustomResourceDefinition>(document)
+                .expect("every document must be a CRD")
+        })
+        .collect::<Vec<_>>();
+
+    for (crd, (version, has_baz)) in crds.iter().zip([("v1alpha1", false), ("v1", true)]) {
+        assert_eq!(crd.spec.group, "stackable.tech");
+        assert_eq!(crd.spec.names.kind, "Foo");
+        assert_eq!(crd.spec.versions.len(), 1);
+
+        let crd_version = &crd.spec.versions[0];
+        assert_eq!(crd_version.name, version);
+
+        let spec_properties = crd_version
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.open_api_v3_schema.as_ref())
+            .and_then(|schema| schema.properties.as_ref())
+            .and_then(|properties| properties.get("spec"))
+            .and_then(|spec| spec.properties.as_ref())
+            .expect("the CRD must contain the spec schema");
+        assert!(spec_properties.contains_key("bar"));
+        assert_eq!(spec_properties.contains_key("baz"), has_baz);
+    }
+}
@@ -0,0 +1,49 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1beta1"),
+    version(name = "v1")
+)]
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+pub struct Foo {
+    #[versioned(deprecated(since = "v1beta1", note = "not needed"))]
+    deprecated_bar: usize,
+    baz: bool,
+}
+
+#[test]
+fn deprecated_in_and_after_deprecation() {
+    let schema = serde_json::to_value(schemars::schema_for!(v1alpha1::Foo)).unwrap();
+    assert!(schema["properties"]["bar"].get("deprecated").is_none());
+
+    let schema = serde_json::to_value(schemars::schema_for!(v1beta1::Foo)).unwrap();
+    assert_eq!(schema["properties"]["deprecated_bar"]["deprecated"], true);
+
+    let schema = serde_json::to_value(schemars::schema_for!(v1::Foo)).unwrap();
+    assert_eq!(schema["properties"]["deprecated_bar"]["deprecated"], true);
+    assert!(schema["properties"]["baz"].get("deprecated").is_none());
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_version_const() {
+    #[versioned(
+        version(name = "v1alpha1", deprecated),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    #[derive(Debug, PartialEq)]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    assert!(v1alpha1::DEPRECATED);
+    assert!(!v1beta1::DEPRECATED);
+    assert!(!v1::DEPRECATED);
+
+    // Deprecated versions are still fully generated and upgraded
+    let foo = v1::Foo::from(v1beta1::Foo::from(v1alpha1::Foo { bar: 42 }));
+    assert_eq!(foo, v1::Foo { bar: 42 });
+}
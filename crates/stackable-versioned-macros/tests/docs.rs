@@ -0,0 +1,38 @@
+use stackable_versioned_macros::versioned;
+
+/// Docs of the container.
+#[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+pub struct Foo {
+    /// Docs of bar.
+    #[versioned(deprecated(since = "v1", note = "not needed"))]
+    deprecated_bar: usize,
+
+    /// Docs of baz.
+    #[versioned(renamed(since = "v1", from = "qux"))]
+    baz: bool,
+}
+
+#[test]
+fn docs_survive_every_version() {
+    let schema = serde_json::to_value(schemars::schema_for!(v1alpha1::Foo)).unwrap();
+    assert_eq!(schema["description"], "Docs of the container.");
+    assert_eq!(schema["properties"]["bar"]["description"], "Docs of bar.");
+    assert_eq!(schema["properties"]["qux"]["description"], "Docs of baz.");
+
+    let schema = serde_json::to_value(schemars::schema_for!(v1::Foo)).unwrap();
+    assert_eq!(schema["description"], "Docs of the container.");
+    assert_eq!(schema["properties"]["baz"]["description"], "Docs of baz.");
+}
+
+#[test]
+fn docs_note_deprecation() {
+    let schema = serde_json::to_value(schemars::schema_for!(v1::Foo)).unwrap();
+    let description = schema["properties"]["deprecated_bar"]["description"]
+        .as_str()
+        .unwrap();
+
+    assert!(description.starts_with("Docs of bar."));
+    assert!(description.ends_with("Deprecated since `v1`: not needed"));
+}
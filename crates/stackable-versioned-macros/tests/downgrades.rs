@@ -0,0 +1,54 @@
+use stackable_versioned_macros::versioned;
+
+#[test]
+#[allow(deprecated)]
+fn downgrades_struct() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        downgrades
+    )]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+
+        #[versioned(
+            renamed(since = "v1beta1", from = "qux"),
+            deprecated(since = "v1", note = "not needed")
+        )]
+        deprecated_baz: bool,
+    }
+
+    let foo = v1::Foo {
+        bar: 42,
+        deprecated_baz: true,
+    };
+
+    let foo = v1beta1::Foo::try_from(foo).expect("structs can always be downgraded");
+    assert_eq!(foo.bar, 42);
+    assert!(foo.baz);
+
+    // The field added in v1beta1 is dropped
+    let foo = v1alpha1::Foo::try_from(foo).expect("structs can always be downgraded");
+    assert!(foo.qux);
+}
+
+#[test]
+fn downgrades_enum() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), downgrades)]
+    pub enum Foo {
+        #[versioned(added(since = "v1"))]
+        Bar,
+
+        #[versioned(renamed(since = "v1", from = "Qux"))]
+        Baz,
+    }
+
+    let foo = v1alpha1::Foo::try_from(v1::Foo::Baz);
+    assert!(matches!(foo, Ok(v1alpha1::Foo::Qux)));
+
+    // The variant added in v1 has no representation in v1alpha1
+    let foo = v1alpha1::Foo::try_from(v1::Foo::Bar);
+    assert!(matches!(foo, Err("Bar")));
+}
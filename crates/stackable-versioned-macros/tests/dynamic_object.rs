@@ -0,0 +1,74 @@
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind};
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1"),
+    k8s(group = "s3.stackable.tech", try_from_dynamic_object)
+)]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FooSpec {
+    #[versioned(renamed(since = "v1", from = "bucket"))]
+    bucket_name: String,
+}
+
+fn dynamic_object(version: &str, spec: serde_json::Value) -> DynamicObject {
+    let gvk = GroupVersionKind::gvk("s3.stackable.tech", version, "Foo");
+    DynamicObject::new("foo", &ApiResource::from_gvk(&gvk)).data(serde_json::json!({
+        "spec": spec,
+    }))
+}
+
+#[test]
+fn try_from_matching_dynamic_object() {
+    let object = dynamic_object("v1", serde_json::json!({ "bucketName": "data" }));
+
+    let spec = v1::FooSpec::try_from(&object).expect("apiVersion must match");
+    assert_eq!(
+        spec,
+        v1::FooSpec {
+            bucket_name: "data".to_owned()
+        }
+    );
+
+    let object = dynamic_object("v1alpha1", serde_json::json!({ "bucket": "data" }));
+    let spec = v1alpha1::FooSpec::try_from(&object).expect("apiVersion must match");
+    assert_eq!(
+        spec,
+        v1alpha1::FooSpec {
+            bucket: "data".to_owned()
+        }
+    );
+}
+
+#[test]
+fn try_from_non_matching_dynamic_object() {
+    // The spec would be valid for v1alpha1 as well, but must not be
+    // deserialized because the apiVersion differs.
+    let object = dynamic_object("v1", serde_json::json!({ "bucket": "data" }));
+
+    let err = v1alpha1::FooSpec::try_from(&object).expect_err("apiVersion must not match");
+    assert!(err.to_string().contains("s3.stackable.tech/v1alpha1"));
+
+    let mut object = dynamic_object("v1alpha1", serde_json::json!({ "bucket": "data" }));
+    object.types = None;
+    assert!(v1alpha1::FooSpec::try_from(&object).is_err());
+}
+
+#[test]
+fn try_from_dynamic_object_generic() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        k8s(group = "s3.stackable.tech", try_from_dynamic_object)
+    )]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    pub struct BarSpec<T> {
+        value: T,
+    }
+
+    let object = dynamic_object("v1", serde_json::json!({ "value": 42 }));
+    let spec = v1::BarSpec::<u32>::try_from(&object).expect("apiVersion must match");
+    assert_eq!(spec, v1::BarSpec { value: 42 });
+}
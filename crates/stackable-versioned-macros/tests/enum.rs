@@ -21,3 +21,40 @@ fn versioned_enum() {
     // TODO (@Techassi): Forward derive PartialEq
     assert!(matches!(v1_foo, v1::Foo::Baz))
 }
+
+#[test]
+#[allow(deprecated)]
+fn versioned_enum_data() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    #[derive(Debug, PartialEq)]
+    pub enum Foo {
+        Bar(usize, String),
+
+        #[versioned(renamed(since = "v1", from = "Qux"))]
+        Baz {
+            quux: bool,
+        },
+
+        #[versioned(deprecated(since = "v1", note = "gone"))]
+        DeprecatedCorge(u16),
+
+        #[versioned(added(since = "v1"))]
+        Grault(u16),
+    }
+
+    assert_eq!(
+        v1::Foo::from(v1alpha1::Foo::Bar(42, "bar".to_owned())),
+        v1::Foo::Bar(42, "bar".to_owned())
+    );
+    assert_eq!(
+        v1::Foo::from(v1alpha1::Foo::Qux { quux: true }),
+        v1::Foo::Baz { quux: true }
+    );
+    assert_eq!(
+        v1::Foo::from(v1alpha1::Foo::Corge(8080)),
+        v1::Foo::DeprecatedCorge(8080)
+    );
+
+    // The variant added in v1 can't be constructed in v1alpha1
+    let _ = v1::Foo::Grault(8080);
+}
@@ -51,3 +51,62 @@ fn from_custom_default_fn() {
     assert_eq!(foo_v1beta1.bar, 42);
     assert!(foo_v1beta1.baz);
 }
+
+#[test]
+fn from_renamed_twice() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    pub struct Foo {
+        #[versioned(
+            renamed(since = "v1beta1", from = "bat"),
+            renamed(since = "v1", from = "qux")
+        )]
+        baz: usize,
+    }
+
+    let foo_v1alpha1 = v1alpha1::Foo { bat: 42 };
+    let foo_v1beta1 = v1beta1::Foo::from(foo_v1alpha1);
+    assert_eq!(foo_v1beta1.qux, 42);
+
+    let foo_v1 = v1::Foo::from(foo_v1beta1);
+    assert_eq!(foo_v1.baz, 42);
+}
+
+#[test]
+fn from_added_in_later_version() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1alpha2"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"), renamed(since = "v1", from = "qux"))]
+        bar: usize,
+        baz: bool,
+    }
+
+    let foo_v1alpha1 = v1alpha1::Foo { baz: true };
+    let foo_v1alpha2 = v1alpha2::Foo::from(foo_v1alpha1);
+    let foo_v1beta1 = v1beta1::Foo::from(foo_v1alpha2);
+    assert_eq!(foo_v1beta1.qux, 0);
+
+    let foo_v1 = v1::Foo::from(foo_v1beta1);
+    assert_eq!(foo_v1.bar, 0);
+    assert!(foo_v1.baz);
+}
+
+#[test]
+fn from_custom_name() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), from_name = "source")]
+    pub struct Foo {
+        #[versioned(renamed(since = "v1", from = "bat"))]
+        bar: usize,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo { bat: 42 });
+    assert_eq!(foo.bar, 42);
+}
@@ -0,0 +1,45 @@
+use stackable_versioned_macros::versioned;
+
+#[test]
+fn generic_struct() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    pub struct Foo<T>
+    where
+        T: Default,
+    {
+        #[versioned(renamed(since = "v1", from = "bar"))]
+        inner: T,
+
+        #[versioned(added(since = "v1"))]
+        baz: bool,
+    }
+
+    let foo_v1alpha1 = v1alpha1::Foo { bar: 42usize };
+    let foo_v1 = v1::Foo::from(foo_v1alpha1);
+
+    assert_eq!(foo_v1.inner, 42);
+    assert!(!foo_v1.baz);
+
+    let foo: FooLatest<String> = v1::Foo {
+        inner: "foo".to_owned(),
+        baz: true,
+    };
+    assert_eq!(foo.inner, "foo");
+}
+
+#[test]
+fn generic_enum() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    #[derive(Debug, PartialEq)]
+    pub enum Foo<T> {
+        #[versioned(renamed(since = "v1", from = "Bar"))]
+        Inner(T),
+        Baz,
+    }
+
+    assert_eq!(
+        v1::Foo::from(v1alpha1::Foo::Bar("foo")),
+        v1::Foo::Inner("foo")
+    );
+    assert_eq!(v1::Foo::<u16>::from(v1alpha1::Foo::Baz), v1::Foo::Baz);
+}
@@ -0,0 +1,16 @@
+use std::collections::BTreeMap;
+
+use stackable_versioned_macros::versioned;
+
+fn main() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    struct Foo {
+        #[versioned(passthrough, renamed(since = "v1beta1", from = "extra"))]
+        overrides: BTreeMap<String, String>,
+        baz: bool,
+    }
+}
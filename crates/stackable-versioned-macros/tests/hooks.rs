@@ -0,0 +1,117 @@
+use std::{any::type_name, cell::RefCell};
+
+use stackable_versioned_macros::versioned;
+
+thread_local! {
+    static CALLS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn pre_convert<T>(_source: &T) {
+    CALLS.with_borrow_mut(|calls| calls.push(format!("pre {}", type_name::<T>())));
+}
+
+fn post_convert<T>(_produced: &T) {
+    CALLS.with_borrow_mut(|calls| calls.push(format!("post {}", type_name::<T>())));
+}
+
+fn take_calls() -> Vec<String> {
+    CALLS.with_borrow_mut(std::mem::take)
+}
+
+#[test]
+fn hooks_struct() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        hooks(pre_convert = "pre_convert", post_convert = "post_convert")
+    )]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+        baz: bool,
+    }
+
+    let foo = v1alpha1::Foo { baz: true };
+    let foo: v1beta1::Foo = foo.into();
+    let foo: v1::Foo = foo.into();
+    assert!(foo.baz);
+    assert_eq!(foo.bar, 0);
+
+    let calls = take_calls();
+    assert_eq!(calls.len(), 4);
+    assert!(calls[0].starts_with("pre ") && calls[0].ends_with("v1alpha1::Foo"));
+    assert!(calls[1].starts_with("post ") && calls[1].ends_with("v1beta1::Foo"));
+    assert!(calls[2].starts_with("pre ") && calls[2].ends_with("v1beta1::Foo"));
+    assert!(calls[3].starts_with("post ") && calls[3].ends_with("v1::Foo"));
+}
+
+#[test]
+fn hooks_enum() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        hooks(pre_convert = "pre_convert", post_convert = "post_convert")
+    )]
+    pub enum Foo {
+        #[versioned(renamed(since = "v1", from = "Qux"))]
+        Bar,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo::Qux);
+    assert!(matches!(foo, v1::Foo::Bar));
+
+    let calls = take_calls();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("pre ") && calls[0].ends_with("v1alpha1::Foo"));
+    assert!(calls[1].starts_with("post ") && calls[1].ends_with("v1::Foo"));
+}
+
+#[test]
+fn hooks_single() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        hooks(post_convert = "post_convert")
+    )]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    let foo = v1::Foo::from(v1alpha1::Foo { bar: 42 });
+    assert_eq!(foo.bar, 42);
+
+    let calls = take_calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].starts_with("post ") && calls[0].ends_with("v1::Foo"));
+}
+
+#[test]
+fn hooks_downgrade() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        downgrades,
+        hooks(pre_convert = "pre_convert", post_convert = "post_convert")
+    )]
+    pub enum Foo {
+        Bar,
+        #[versioned(added(since = "v1"))]
+        Baz,
+    }
+
+    let foo = v1alpha1::Foo::try_from(v1::Foo::Bar).expect("Bar exists in v1alpha1");
+    assert!(matches!(foo, v1alpha1::Foo::Bar));
+
+    let calls = take_calls();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("pre ") && calls[0].ends_with("v1::Foo"));
+    assert!(calls[1].starts_with("post ") && calls[1].ends_with("v1alpha1::Foo"));
+
+    // The post_convert hook is only called for successful conversions
+    assert!(v1alpha1::Foo::try_from(v1::Foo::Baz).is_err());
+
+    let calls = take_calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].starts_with("pre ") && calls[0].ends_with("v1::Foo"));
+}
@@ -0,0 +1,41 @@
+use stackable_versioned_macros::versioned;
+
+#[test]
+fn latest_alias() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+        baz: bool,
+    }
+
+    // This only compiles if the alias points to v1, because v1alpha1::Foo
+    // doesn't have the bar field.
+    let foo: FooLatest = v1::Foo { bar: 42, baz: true };
+    assert_eq!(
+        std::any::type_name_of_val(&foo),
+        std::any::type_name::<v1::Foo>()
+    );
+    assert_eq!(foo.bar, 42);
+}
+
+#[test]
+fn latest_alias_enum() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    #[allow(dead_code)]
+    pub enum Foo {
+        Bar,
+        #[versioned(added(since = "v1"))]
+        Baz,
+    }
+
+    // This only compiles if the alias points to v1, because v1alpha1::Foo
+    // doesn't have the Baz variant.
+    let foo: FooLatest = v1::Foo::Baz;
+    assert!(matches!(foo, v1::Foo::Baz));
+}
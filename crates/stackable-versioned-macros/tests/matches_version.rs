@@ -0,0 +1,61 @@
+use rstest::rstest;
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1"),
+    k8s(group = "s3.stackable.tech")
+)]
+#[allow(dead_code)]
+pub struct Foo {
+    bar: usize,
+}
+
+#[rstest]
+#[case("s3.stackable.tech/v1alpha1", true, false)]
+#[case("s3.stackable.tech/v1", false, true)]
+#[case("s3.stackable.tech/v1beta1", false, false)]
+#[case("stackable.tech/v1", false, false)]
+#[case("v1", false, false)]
+#[case("", false, false)]
+fn matches_version_with_group(
+    #[case] api_version: &str,
+    #[case] expected_v1alpha1: bool,
+    #[case] expected_v1: bool,
+) {
+    assert_eq!(
+        v1alpha1::Foo::matches_version(api_version),
+        expected_v1alpha1
+    );
+    assert_eq!(v1::Foo::matches_version(api_version), expected_v1);
+}
+
+#[rstest]
+#[case("v1alpha1", true, false)]
+#[case("v1", false, true)]
+#[case("s3.stackable.tech/v1", false, false)]
+fn matches_version_without_group(
+    #[case] api_version: &str,
+    #[case] expected_v1alpha1: bool,
+    #[case] expected_v1: bool,
+) {
+    // The enum is declared in the scope of the test, because the modules of
+    // both containers would collide otherwise.
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    #[allow(dead_code)]
+    pub enum Bar {
+        Baz,
+    }
+
+    assert_eq!(
+        v1alpha1::Bar::matches_version(api_version),
+        expected_v1alpha1
+    );
+    assert_eq!(v1::Bar::matches_version(api_version), expected_v1);
+}
+
+#[test]
+fn api_version_const() {
+    assert_eq!(v1alpha1::API_VERSION, "s3.stackable.tech/v1alpha1");
+    assert_eq!(v1::API_VERSION, "s3.stackable.tech/v1");
+}
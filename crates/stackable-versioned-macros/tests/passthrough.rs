@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use stackable_versioned_macros::versioned;
+
+#[test]
+fn passthrough_survives_conversion() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1")
+    )]
+    pub struct Foo {
+        #[versioned(passthrough)]
+        extra: BTreeMap<String, String>,
+
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+    }
+
+    let extra = BTreeMap::from([
+        ("unknown".to_owned(), "value".to_owned()),
+        ("other".to_owned(), "data".to_owned()),
+    ]);
+
+    let foo_v1alpha1 = v1alpha1::Foo {
+        extra: extra.clone(),
+    };
+    let foo_v1beta1 = v1beta1::Foo::from(foo_v1alpha1);
+    let foo_v1 = v1::Foo::from(foo_v1beta1);
+
+    assert_eq!(foo_v1.extra, extra);
+    assert_eq!(foo_v1.bar, 0);
+}
+
+#[test]
+fn renamed_passthrough_survives_conversion() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+    pub struct Foo {
+        #[versioned(passthrough, renamed(since = "v1", from = "extra"))]
+        overrides: BTreeMap<String, String>,
+    }
+
+    let extra = BTreeMap::from([("unknown".to_owned(), "value".to_owned())]);
+
+    let foo_v1alpha1 = v1alpha1::Foo {
+        extra: extra.clone(),
+    };
+    let foo_v1 = v1::Foo::from(foo_v1alpha1);
+
+    assert_eq!(foo_v1.overrides, extra);
+}
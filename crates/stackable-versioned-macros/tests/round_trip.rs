@@ -0,0 +1,104 @@
+use std::net::IpAddr;
+
+use stackable_versioned_macros::versioned;
+
+fn parse_addr(addr: String) -> IpAddr {
+    addr.parse().expect("test addresses must be valid")
+}
+
+fn format_addr(addr: IpAddr) -> String {
+    addr.to_string()
+}
+
+fn format_addr_lossy(_addr: IpAddr) -> String {
+    "0.0.0.0".to_owned()
+}
+
+#[test]
+fn round_trip() {
+    #[versioned(version(name = "v1alpha1"), version(name = "v1"), round_trip_tests)]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Foo {
+        bar: usize,
+        #[versioned(renamed(since = "v1", from = "qux"))]
+        baz: bool,
+    }
+
+    v1alpha1::Foo { bar: 42, qux: true }.assert_round_trip();
+}
+
+#[test]
+fn round_trip_added_field() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        downgrades,
+        round_trip_tests
+    )]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Foo {
+        bar: usize,
+        #[versioned(added(since = "v1beta1"))]
+        baz: String,
+    }
+
+    // Only the fields present in both versions are compared
+    v1alpha1::Foo { bar: 42 }.assert_round_trip();
+    v1beta1::Foo {
+        bar: 42,
+        baz: "baz".to_owned(),
+    }
+    .assert_round_trip();
+}
+
+#[test]
+fn round_trip_changed_field() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        downgrades,
+        round_trip_tests
+    )]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Foo {
+        #[versioned(changed(
+            since = "v1",
+            from_type = "String",
+            with = "parse_addr",
+            downgrade_with = "format_addr"
+        ))]
+        addr: IpAddr,
+    }
+
+    v1alpha1::Foo {
+        addr: "127.0.0.1".to_owned(),
+    }
+    .assert_round_trip();
+}
+
+#[test]
+#[should_panic(expected = "field `addr` of `v1alpha1::Foo` is not preserved by converting to `v1`")]
+fn round_trip_lossy_converter() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        downgrades,
+        round_trip_tests
+    )]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Foo {
+        #[versioned(changed(
+            since = "v1",
+            from_type = "String",
+            with = "parse_addr",
+            downgrade_with = "format_addr_lossy"
+        ))]
+        addr: IpAddr,
+    }
+
+    v1alpha1::Foo {
+        addr: "127.0.0.1".to_owned(),
+    }
+    .assert_round_trip();
+}
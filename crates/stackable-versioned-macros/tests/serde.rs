@@ -0,0 +1,46 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(version(name = "v1alpha1"), version(name = "v1"))]
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FooSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bucket_name: Option<String>,
+
+    #[versioned(renamed(since = "v1", from = "endpoint_host"))]
+    #[serde(rename = "host")]
+    connection_host: String,
+}
+
+#[test]
+fn serde_attributes_forwarded() {
+    let foo = v1alpha1::FooSpec {
+        bucket_name: None,
+        endpoint_host: "minio".to_owned(),
+    };
+
+    // The rename refers to the latest name of the field and is thus not
+    // forwarded to earlier versions using a different name.
+    let json = serde_json::to_value(&foo).unwrap();
+    assert_eq!(json, serde_json::json!({ "endpointHost": "minio" }));
+    assert_eq!(
+        serde_json::from_value::<v1alpha1::FooSpec>(json).unwrap(),
+        foo
+    );
+
+    let foo = v1::FooSpec {
+        bucket_name: Some("bucket".to_owned()),
+        connection_host: "minio".to_owned(),
+    };
+
+    let json = serde_json::to_value(&foo).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "bucketName": "bucket", "host": "minio" })
+    );
+    assert_eq!(serde_json::from_value::<v1::FooSpec>(json).unwrap(), foo);
+
+    // Missing fields use the forwarded serde default
+    let foo: v1::FooSpec = serde_json::from_value(serde_json::json!({ "host": "minio" })).unwrap();
+    assert_eq!(foo.bucket_name, None);
+}
@@ -0,0 +1,33 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1"),
+    k8s(group = "s3.stackable.tech", spec_json_schema)
+)]
+#[derive(schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub struct FooSpec {
+    bucket_name: String,
+
+    #[versioned(added(since = "v1"))]
+    region: Option<String>,
+}
+
+#[test]
+fn spec_json_schema_properties() {
+    let schema = v1alpha1::FooSpec::spec_json_schema();
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(properties.contains_key("bucketName"));
+    assert!(!properties.contains_key("region"));
+    assert_eq!(schema["required"], serde_json::json!(["bucketName"]));
+
+    let schema = v1::FooSpec::spec_json_schema();
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(properties.contains_key("bucketName"));
+    assert!(properties.contains_key("region"));
+
+    // The schema is cached, but must be identical for every call.
+    assert_eq!(v1::FooSpec::spec_json_schema(), schema);
+}
@@ -17,6 +17,7 @@
 //     mod attributes_struct;
 //     mod basic;
 //     mod deprecate;
+//     mod passthrough;
 //     mod rename;
 //     mod skip_from_version;
 // }
@@ -28,8 +29,14 @@
 // #[allow(dead_code)]
 // mod bad {
 //     mod deprecate;
+//     mod duplicate_version;
+//     mod invalid_from_name;
+//     mod passthrough_deprecated;
 //     mod skip_from_all;
+//     mod skip_from_last_version;
 //     mod skip_from_version;
+//     mod unsorted_versions;
+//     mod version_enum_latest_not_last;
 // }
 
 #[test]
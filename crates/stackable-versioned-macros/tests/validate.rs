@@ -0,0 +1,101 @@
+use stackable_versioned_macros::versioned;
+
+#[derive(Debug, PartialEq)]
+enum ValidationError {
+    MissingHost,
+}
+
+#[versioned(
+    version(name = "v1alpha1"),
+    version(name = "v1"),
+    validate(with = "validate_connection", error = "ValidationError")
+)]
+pub struct Connection {
+    host: Option<String>,
+
+    #[versioned(added(since = "v1"))]
+    tls: bool,
+}
+
+/// Abstracts over all versions of the connection, so that a single validation
+/// function can be used. Only connections of version v1 support TLS.
+trait ConnectionDetails {
+    fn host(&self) -> Option<&str>;
+
+    fn tls(&self) -> bool {
+        false
+    }
+}
+
+impl ConnectionDetails for v1alpha1::Connection {
+    fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+}
+
+impl ConnectionDetails for v1::Connection {
+    fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    fn tls(&self) -> bool {
+        self.tls
+    }
+}
+
+fn validate_connection(connection: &impl ConnectionDetails) -> Result<(), ValidationError> {
+    if connection.tls() && connection.host().is_none() {
+        return Err(ValidationError::MissingHost);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn validate_passes() {
+    let connection = v1alpha1::Connection { host: None };
+    assert_eq!(connection.validate(), Ok(()));
+
+    let connection = v1::Connection {
+        host: Some("minio".to_owned()),
+        tls: true,
+    };
+    assert_eq!(connection.validate(), Ok(()));
+}
+
+#[test]
+fn validate_fails() {
+    let connection = v1::Connection {
+        host: None,
+        tls: true,
+    };
+    assert_eq!(connection.validate(), Err(ValidationError::MissingHost));
+}
+
+#[test]
+fn validate_enum() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1"),
+        validate(with = "validate_mode", error = "String")
+    )]
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    pub enum Mode {
+        Simple,
+
+        #[versioned(added(since = "v1"))]
+        Unsupported,
+    }
+
+    fn validate_mode<T: std::fmt::Debug>(mode: &T) -> Result<(), String> {
+        let mode = format!("{mode:?}");
+        match mode.as_str() {
+            "Unsupported" => Err(format!("mode {mode} is not supported")),
+            _ => Ok(()),
+        }
+    }
+
+    assert_eq!(v1::Mode::Simple.validate(), Ok(()));
+    assert!(v1::Mode::Unsupported.validate().is_err());
+}
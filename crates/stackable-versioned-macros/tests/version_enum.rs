@@ -0,0 +1,246 @@
+use stackable_versioned_macros::versioned;
+
+#[test]
+fn version_enum_ordering() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version_enum
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+        baz: bool,
+    }
+
+    assert!(FooVersion::V1Alpha1 < FooVersion::V1Beta1);
+    assert!(FooVersion::V1Beta1 < FooVersion::V1);
+    assert!(FooVersion::V1Alpha1 < FooVersion::V1);
+
+    let stored_version = FooVersion::V1Beta1;
+    let latest_version = [FooVersion::V1, FooVersion::V1Alpha1, FooVersion::V1Beta1]
+        .into_iter()
+        .max();
+    assert_eq!(latest_version, Some(FooVersion::V1));
+    assert!(Some(stored_version) < latest_version);
+}
+
+#[test]
+fn version_enum_as_str_from_str() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version_enum
+    )]
+    #[allow(dead_code)]
+    pub enum Foo {
+        Bar,
+    }
+
+    assert_eq!(FooVersion::V1Alpha1.as_str(), "v1alpha1");
+    assert_eq!(FooVersion::V1Beta1.as_str(), "v1beta1");
+    assert_eq!(FooVersion::V1.as_str(), "v1");
+
+    for version in [FooVersion::V1Alpha1, FooVersion::V1Beta1, FooVersion::V1] {
+        assert_eq!(version.as_str().parse(), Ok(version));
+    }
+
+    assert_eq!("v2".parse::<FooVersion>(), Err("v2".to_owned()));
+}
+
+#[test]
+fn version_enum_unsorted() {
+    #[versioned(
+        version(name = "v1"),
+        version(name = "v1alpha1"),
+        version(name = "v2beta3"),
+        options(allow_unsorted),
+        version_enum
+    )]
+    #[allow(dead_code)]
+    pub enum Foo {
+        Bar,
+    }
+
+    // The ordering follows the Kubernetes version precedence, not the order of
+    // declaration.
+    assert!(FooVersion::V1Alpha1 < FooVersion::V1);
+    assert!(FooVersion::V1 < FooVersion::V2Beta3);
+    assert!(FooVersion::V1Alpha1 < FooVersion::V2Beta3);
+}
+
+#[test]
+fn any_version_try_into_latest() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version_enum
+    )]
+    #[derive(Debug, PartialEq)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+        #[versioned(renamed(since = "v1", from = "old_baz"))]
+        baz: bool,
+    }
+
+    let foo = FooAnyVersion::V1Alpha1(v1alpha1::Foo { old_baz: true });
+    assert_eq!(foo.version(), FooVersion::V1Alpha1);
+
+    let (latest, path) = foo.try_into_latest().unwrap();
+    assert_eq!(latest, v1::Foo { bar: 0, baz: true });
+    assert_eq!(path, ["v1alpha1", "v1beta1", "v1"]);
+
+    let foo = FooAnyVersion::V1Beta1(v1beta1::Foo {
+        bar: 42,
+        old_baz: false,
+    });
+    let (latest, path) = foo.try_into_latest().unwrap();
+    assert_eq!(
+        latest,
+        v1::Foo {
+            bar: 42,
+            baz: false
+        }
+    );
+    assert_eq!(path, ["v1beta1", "v1"]);
+
+    let foo = FooAnyVersion::V1(v1::Foo { bar: 1, baz: true });
+    let (latest, path) = foo.try_into_latest().unwrap();
+    assert_eq!(latest, v1::Foo { bar: 1, baz: true });
+    assert_eq!(path, ["v1"]);
+}
+
+#[test]
+fn any_version_try_into_latest_skipped_from() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1", skip(from)),
+        version(name = "v1"),
+        version_enum
+    )]
+    #[derive(Debug, PartialEq)]
+    #[allow(dead_code)]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    let foo = FooAnyVersion::V1Alpha1(v1alpha1::Foo { bar: 1 });
+    assert_eq!(foo.try_into_latest().unwrap_err(), FooVersion::V1Beta1);
+
+    let foo = FooAnyVersion::V1(v1::Foo { bar: 1 });
+    assert_eq!(
+        foo.try_into_latest().unwrap(),
+        (v1::Foo { bar: 1 }, vec!["v1"])
+    );
+}
+
+#[test]
+fn removed_fields_between() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version_enum
+    )]
+    #[allow(dead_code)]
+    pub struct Foo {
+        #[versioned(deprecated(since = "v1beta1", note = "gone"))]
+        deprecated_bar: usize,
+
+        #[versioned(
+            renamed(since = "v1beta1", from = "old_baz"),
+            deprecated(since = "v1", note = "gone")
+        )]
+        deprecated_baz: bool,
+
+        #[versioned(added(since = "v1beta1"))]
+        qux: String,
+
+        quux: u16,
+    }
+
+    let removed = |from, to| FooVersion::removed_fields_between(from, to);
+
+    assert_eq!(removed("v1alpha1", "v1beta1"), Ok(vec!["bar"]));
+    assert_eq!(removed("v1beta1", "v1"), Ok(vec!["baz"]));
+    assert_eq!(removed("v1alpha1", "v1"), Ok(vec!["bar", "old_baz"]));
+    assert_eq!(removed("v1", "v1"), Ok(vec![]));
+
+    // Fields added after the target version are removed by a downgrade
+    assert_eq!(removed("v1beta1", "v1alpha1"), Ok(vec!["qux"]));
+
+    assert_eq!(removed("v2", "v1"), Err("v2"));
+    assert_eq!(removed("v1", "v1beta2"), Err("v1beta2"));
+}
+
+#[test]
+fn upgrade_to_latest() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1"),
+        version(name = "v1"),
+        version_enum,
+        upgrade_to_latest
+    )]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    pub struct Foo {
+        #[versioned(added(since = "v1beta1"))]
+        bar: usize,
+        #[versioned(renamed(since = "v1", from = "old_baz"))]
+        baz: bool,
+    }
+
+    let foo = FooVersion::upgrade_to_latest("v1alpha1", serde_json::json!({ "old_baz": true }));
+    assert_eq!(foo.unwrap(), v1::Foo { bar: 0, baz: true });
+
+    let foo = FooVersion::upgrade_to_latest(
+        "v1beta1",
+        serde_json::json!({ "bar": 42, "old_baz": false }),
+    );
+    assert_eq!(
+        foo.unwrap(),
+        v1::Foo {
+            bar: 42,
+            baz: false
+        }
+    );
+
+    // The value must match the given version
+    let err = FooVersion::upgrade_to_latest("v1", serde_json::json!({ "old_baz": true }));
+    assert!(err.is_err());
+
+    let err = FooVersion::upgrade_to_latest("v2", serde_json::json!({ "baz": true }));
+    assert_eq!(
+        err.unwrap_err().to_string(),
+        r#"unknown version "v2" of `Foo`, expected one of ["v1alpha1", "v1beta1", "v1"]"#
+    );
+}
+
+#[test]
+fn upgrade_to_latest_skipped_from() {
+    #[versioned(
+        version(name = "v1alpha1"),
+        version(name = "v1beta1", skip(from)),
+        version(name = "v1"),
+        version_enum,
+        upgrade_to_latest
+    )]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    pub struct Foo {
+        bar: usize,
+    }
+
+    let err = FooVersion::upgrade_to_latest("v1alpha1", serde_json::json!({ "bar": 1 }));
+    assert_eq!(
+        err.unwrap_err().to_string(),
+        "cannot upgrade `Foo` from version v1beta1, because its `From` implementation is skipped"
+    );
+
+    let foo = FooVersion::upgrade_to_latest("v1", serde_json::json!({ "bar": 1 }));
+    assert_eq!(foo.unwrap(), v1::Foo { bar: 1 });
+}